@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+// Puzzle answer, either a number or text such as a letter grid drawn in ASCII art
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    // i128 so every u64 answer fits too
+    Number(i128),
+    Text(Cow<'static, str>),
+}
+
+impl Answer {
+    pub const fn text(text: &'static str) -> Answer {
+        Answer::Text(Cow::Borrowed(text))
+    }
+
+    // Multi-line answers are printed starting on their own line
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Text(text) if text.contains('\n'))
+    }
+
+    // For tables, where a multiline answer would break the rows
+    pub fn one_line(&self) -> String {
+        match self.is_multiline() {
+            true => "(multiline answer)".to_string(),
+            false => self.to_string(),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Number(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(Cow::Owned(value))
+    }
+}
+
+impl From<&'static str> for Answer {
+    fn from(value: &'static str) -> Self {
+        Answer::text(value)
+    }
+}
+
+// JSON readers only take integers that fit an i64 or a u64, anything past that is a float
+fn fits_json_integer(number: i128) -> bool {
+    number >= i64::MIN as i128 && number <= u64::MAX as i128
+}
+
+// Stored as a JSON number when it's a number so the answers manifest stays readable. Numbers
+// too big for that are stored as strings and read back as numbers.
+impl serde::Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Number(number) if fits_json_integer(*number) => serializer.serialize_i128(*number),
+            Answer::Number(number) => serializer.serialize_str(&number.to_string()),
+            Answer::Text(text) => serializer.serialize_str(text),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Answer {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AnswerVisitor;
+
+        impl serde::de::Visitor<'_> for AnswerVisitor {
+            type Value = Answer;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "an integer or a string")
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Answer, E> {
+                Ok(value.into())
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Answer, E> {
+                Ok(value.into())
+            }
+
+            fn visit_i128<E: serde::de::Error>(self, value: i128) -> Result<Answer, E> {
+                Ok(value.into())
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Answer, E> {
+                match value.parse::<i128>() {
+                    Ok(number) if !fits_json_integer(number) => Ok(number.into()),
+                    _ => Ok(value.to_string().into()),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(answer: Answer) -> Answer {
+        serde_json::from_str(&serde_json::to_string(&answer).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn numbers_round_trip() {
+        for number in [0, -5, i64::MIN as i128, i64::MAX as i128, u64::MAX as i128, u64::MAX as i128 + 1, i128::MIN, i128::MAX] {
+            assert_eq!(round_trip(Answer::Number(number)), Answer::Number(number));
+        }
+    }
+
+    #[test]
+    fn big_numbers_are_stored_as_strings() {
+        assert_eq!(serde_json::to_string(&Answer::Number(u64::MAX as i128)).unwrap(), "18446744073709551615");
+        assert_eq!(serde_json::to_string(&Answer::Number(u64::MAX as i128 + 1)).unwrap(), "\"18446744073709551616\"");
+    }
+
+    #[test]
+    fn text_round_trips() {
+        for text in ["EZFCHJAB", "12", "#..#\n.##.\n"] {
+            assert_eq!(round_trip(Answer::from(text.to_string())), Answer::from(text.to_string()));
+        }
+    }
+}
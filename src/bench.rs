@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::day::{Day, Part};
+use crate::error::Result;
+use crate::store;
+
+// Next to the run history, out of reach of cargo clean
+pub const BASELINE_FILE: &str = ".aoc/bench-baseline.json";
+
+#[derive(Clone, Debug)]
+pub struct BenchOptions {
+    // None benches both parts
+    pub part: Option<Part>,
+    // Untimed runs before sampling, to warm caches and the allocator
+    pub warmup: u32,
+    pub samples: u32,
+    // Stops sampling a stage early once it has taken this long, slow parts still get one sample
+    pub max_time: Duration,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Stage {
+    Parse,
+    Part(Part),
+}
+
+impl Stage {
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Part(Part::One) => "part 1",
+            Stage::Part(Part::Two) => "part 2",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Stats {
+    pub samples: usize,
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub mean: Duration,
+    #[serde(with = "nanos")]
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        let count = samples.len();
+        let seconds: Vec<f64> = samples.iter().map(|x| x.as_secs_f64()).collect();
+        let mean = seconds.iter().sum::<f64>() / count as f64;
+        let variance = seconds.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count as f64;
+        let median = match count % 2 {
+            0 => (samples[count / 2 - 1] + samples[count / 2]) / 2,
+            _ => samples[count / 2],
+        };
+
+        Stats {
+            samples: count,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+pub fn bench<TDay: Day + ?Sized>(
+    day: &TDay,
+    file_content: &str,
+    options: &BenchOptions,
+) -> Result<Vec<(Stage, Stats)>> {
+    let mut results = Vec::new();
+
+    let parse_samples = sample(options, || {
+        let file_content = file_content.to_string();
+        let now = Instant::now();
+        black_box(day.parse_file(file_content)?);
+        Ok(now.elapsed())
+    })?;
+    results.push((Stage::Parse, Stats::from_samples(parse_samples)));
+
+    let data = day.parse_file(file_content.to_string())?;
+    let part_2_data = day.parse_part_2(file_content).transpose()?;
+
+    for part in Part::ALL.into_iter().filter(|x| x.is_selected(options.part)) {
+        let part_samples = sample(options, || {
+            let now = Instant::now();
+            let answer = match part {
+                Part::One => day.part_1(&data),
+                Part::Two => day.part_2(part_2_data.as_ref().unwrap_or(&data)),
+            };
+            black_box(answer.map_err(|e| e.with_part(part))?);
+            Ok(now.elapsed())
+        })?;
+        results.push((Stage::Part(part), Stats::from_samples(part_samples)));
+    }
+
+    Ok(results)
+}
+
+fn sample(options: &BenchOptions, mut run: impl FnMut() -> Result<Duration>) -> Result<Vec<Duration>> {
+    let started = Instant::now();
+    for _ in 0..options.warmup {
+        run()?;
+        if started.elapsed() > options.max_time {
+            break;
+        }
+    }
+
+    let started = Instant::now();
+    let mut samples = Vec::with_capacity(options.samples as usize);
+    while samples.len() < options.samples.max(1) as usize {
+        samples.push(run()?);
+        if started.elapsed() > options.max_time {
+            break;
+        }
+    }
+
+    Ok(samples)
+}
+
+// Saved stats keyed by day then stage name, compared against on later runs
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Baseline {
+    days: BTreeMap<u8, BTreeMap<String, Stats>>,
+}
+
+impl Baseline {
+    // A missing baseline just has nothing to compare against
+    pub fn load(path: &str) -> Result<Baseline> {
+        Ok(store::load_json(path)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        store::save_json(path, self)
+    }
+
+    pub fn get(&self, day: u8, stage: Stage) -> Option<&Stats> {
+        self.days.get(&day)?.get(stage.name())
+    }
+
+    pub fn insert(&mut self, day: u8, stage: Stage, stats: Stats) {
+        self.days.entry(day).or_default().insert(stage.name().to_string(), stats);
+    }
+}
+
+pub fn print_header(day: u8, title: &str) {
+    println!("<--------    Benchmarking Day {}: {}    -------->", day, title);
+    println!(
+        "{:<8} {:>7} {:>11} {:>11} {:>11} {:>11}   vs Baseline",
+        "Stage", "Samples", "Min", "Median", "Mean", "Std Dev"
+    );
+}
+
+// How many times faster or slower after is than before
+pub fn speed_change(before: Duration, after: Duration) -> String {
+    let speedup = before.as_secs_f64() / after.as_secs_f64();
+    match speedup >= 1.0 {
+        true => format!("{:.2}x faster", speedup),
+        false => format!("{:.2}x slower", 1.0 / speedup),
+    }
+}
+
+pub fn print_stats(stage: Stage, stats: &Stats, baseline: Option<&Stats>) {
+    // Compared by median since it's the least affected by outliers
+    let comparison = match baseline {
+        Some(baseline) => speed_change(baseline.median, stats.median),
+        None => String::from("-"),
+    };
+
+    println!(
+        "{:<8} {:>7} {:>11} {:>11} {:>11} {:>11}   {}",
+        stage.name(),
+        stats.samples,
+        format!("{:.2?}", stats.min),
+        format!("{:.2?}", stats.median),
+        format!("{:.2?}", stats.mean),
+        format!("{:.2?}", stats.std_dev),
+        comparison
+    );
+}
+
+pub(crate) mod nanos {
+    use std::time::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_nanos() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_nanos(u64::deserialize(deserializer)?))
+    }
+}
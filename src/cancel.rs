@@ -0,0 +1,94 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::error::{Error, Result};
+
+// Tells a running part to give up, either from another thread or once its time budget runs out.
+// Solvers don't hold one, they call check() in their hot loops while the runner has one installed.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    // None never times out
+    pub fn with_timeout(limit: Option<Duration>) -> CancelToken {
+        CancelToken {
+            cancelled: Arc::default(),
+            deadline: limit.map(|x| (Instant::now() + x, x)),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(Error::cancelled(None));
+        }
+        match self.deadline {
+            Some((deadline, limit)) if Instant::now() >= deadline => Err(Error::cancelled(Some(limit))),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Looking at the clock on every call would slow the tightest loops down
+const CHECK_INTERVAL: u32 = 1024;
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+    // Set once the current token has fired, so every later poll sees it straight away
+    static TRIPPED: Cell<bool> = const { Cell::new(false) };
+}
+
+// Runs f with the token installed for polling on this thread
+pub fn scope<T>(token: CancelToken, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.with(|x| x.replace(Some(token)));
+    let was_tripped = TRIPPED.with(|x| x.replace(false));
+    let result = f();
+    CURRENT.with(|x| *x.borrow_mut() = previous);
+    TRIPPED.with(|x| x.set(was_tripped));
+    result
+}
+
+// Cheap enough for the hottest loops, for code that can't easily return a Result.
+// Bail out with any value when it's true and call check() once on the way out, which errors so
+// whatever was returned is thrown away.
+#[inline]
+pub fn is_cancelled() -> bool {
+    if TRIPPED.with(|x| x.get()) {
+        return true;
+    }
+
+    let calls = CALLS.with(|x| {
+        let calls = x.get().wrapping_add(1);
+        x.set(calls);
+        calls
+    });
+    calls.is_multiple_of(CHECK_INTERVAL) && poll_current()
+}
+
+#[cold]
+fn poll_current() -> bool {
+    let tripped = CURRENT.with(|x| x.borrow().as_ref().is_some_and(|token| token.check().is_err()));
+    TRIPPED.with(|x| x.set(tripped));
+    tripped
+}
+
+// Errors once the current part was cancelled or ran out of time, Ok when nothing is installed
+#[inline]
+pub fn check() -> Result<()> {
+    match is_cancelled() {
+        true => CURRENT.with(|x| x.borrow().as_ref().map_or(Ok(()), |token| token.check())),
+        false => Ok(()),
+    }
+}
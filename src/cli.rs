@@ -0,0 +1,253 @@
+use std::time::Duration;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use aoc2022::bench::{BenchOptions, BASELINE_FILE};
+use aoc2022::day::{Part, RunOptions};
+use aoc2022::output::{JsonReporter, Reporter, TextReporter};
+use aoc2022::report::ReportFormat;
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2022 solutions")]
+pub struct Cli {
+    /// Show solver logs on stderr, e.g. "day20=debug" or "trace". RUST_LOG works too.
+    #[arg(long, global = true, value_name = "FILTER")]
+    pub log: Option<String>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run a single day, checking the example before the full input
+    Run {
+        /// Day number, e.g. 16
+        day: u8,
+        /// Run this input file instead of the example and full input, - reads stdin
+        #[arg(short, long, conflicts_with = "record")]
+        input: Option<String>,
+        /// Afterwards show the day's visualization of its first example, or of --input.
+        /// Given a folder, writes each frame to a file in it instead.
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = "-")]
+        visualize: Option<String>,
+        #[command(flatten)]
+        run: RunArgs,
+    },
+    /// Run every registered day
+    All {
+        /// Days to run at once, output stays in day order
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        jobs: u32,
+        /// Also run part 1 and part 2 of a day at once, each part checks its own examples. Needs --jobs 2 or more.
+        #[arg(long)]
+        split_parts: bool,
+        #[command(flatten)]
+        run: RunArgs,
+    },
+    /// Run a day again whenever its input files change and show what changed since the last run
+    Watch {
+        /// Day number, e.g. 20
+        day: u8,
+        /// Seconds between checks for changed inputs
+        #[arg(long, default_value_t = 1.0)]
+        interval: f64,
+        #[command(flatten)]
+        run: RunArgs,
+    },
+    /// Run every implementation of a day, check they agree and see which is fastest
+    Compare {
+        /// Day number, every day with more than one implementation when left out
+        day: Option<u8>,
+        /// Only compare this part (1 or 2)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Only compare on the full input
+        #[arg(long)]
+        skip_example: bool,
+        /// Give up on a part after this many seconds
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Check every implementation of a day against the first on random inputs, shrinking any disagreement
+    Fuzz {
+        /// Day number
+        day: u8,
+        /// Inputs to try
+        #[arg(long, default_value_t = 100)]
+        cases: usize,
+        /// Largest input to generate, in the day's own unit (cubes, numbers, ...)
+        #[arg(long, default_value_t = 50)]
+        size: usize,
+        /// Seed of the first case, random when left out
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Only fuzz this part (1 or 2)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+    },
+    /// Make a random puzzle input for a day, printed unless it's written to a file
+    Gen {
+        /// Day number
+        day: u8,
+        /// Size in the day's own unit: valves for day 16, jets for 17, cubes for 18, numbers for 20
+        size: usize,
+        /// The same seed and size always give the same input, random when left out
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Write the input to this file
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Run every day's full input and write a Markdown or HTML table of the results
+    Report {
+        #[command(flatten)]
+        report: ReportArgs,
+    },
+    /// Show how each part's full input timings changed over past runs
+    History {
+        /// Day number, every day when left out
+        day: Option<u8>,
+        /// Runs to list per part
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Flag a commit when a part's median time grows by more than this factor
+        #[arg(long, default_value_t = 1.2)]
+        threshold: f64,
+    },
+    /// List the registered days
+    List,
+    /// Time parsing and each part over many runs of the full input
+    Bench {
+        /// Day number, every day when left out
+        day: Option<u8>,
+        #[command(flatten)]
+        bench: BenchArgs,
+    },
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Only run this part (1 or 2)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+    /// Only check the example input
+    #[arg(long, conflicts_with = "skip_example")]
+    pub example_only: bool,
+    /// Don't check the example before the full input
+    #[arg(long)]
+    pub skip_example: bool,
+    /// Keep running after a wrong example answer
+    #[arg(long)]
+    pub keep_going: bool,
+    /// Save full input answers missing from answers.json once their example is right
+    #[arg(long)]
+    pub record: bool,
+    /// Give up on a part after this many seconds
+    #[arg(long)]
+    pub timeout: Option<f64>,
+    /// How to print results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(Copy, Clone, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    /// One JSON record per line
+    Json,
+}
+
+impl Format {
+    pub fn reporter(self) -> Box<dyn Reporter> {
+        match self {
+            Format::Text => Box::new(TextReporter),
+            Format::Json => Box::new(JsonReporter::default()),
+        }
+    }
+}
+
+impl RunArgs {
+    pub fn options(&self) -> RunOptions {
+        RunOptions {
+            part: self.part.and_then(Part::from_number),
+            run_example: !self.skip_example,
+            run_full: !self.example_only,
+            stop_on_mismatch: !self.keep_going,
+            timeout: self.timeout.map(|x| Duration::from_secs_f64(x.max(0.0))),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ReportArgs {
+    /// Table format
+    #[arg(long, value_enum, default_value_t = ReportFormatArg::Markdown)]
+    pub format: ReportFormatArg,
+    /// File to write, stdout when left out
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// Days to run at once
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub jobs: u32,
+    /// Give up on a part after this many seconds
+    #[arg(long)]
+    pub timeout: Option<f64>,
+    /// Bench baseline to compare timings against
+    #[arg(long, default_value = BASELINE_FILE)]
+    pub baseline: String,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum ReportFormatArg {
+    Markdown,
+    Html,
+}
+
+impl ReportArgs {
+    pub fn options(&self) -> RunOptions {
+        RunOptions {
+            run_example: false,
+            stop_on_mismatch: false,
+            timeout: self.timeout.map(|x| Duration::from_secs_f64(x.max(0.0))),
+            ..RunOptions::default()
+        }
+    }
+
+    pub fn report_format(&self) -> ReportFormat {
+        match self.format {
+            ReportFormatArg::Markdown => ReportFormat::Markdown,
+            ReportFormatArg::Html => ReportFormat::Html,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Only bench this part (1 or 2), parsing is always benched
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+    /// Timed runs per stage
+    #[arg(long, default_value_t = 20)]
+    pub samples: u32,
+    /// Untimed runs per stage before sampling
+    #[arg(long, default_value_t = 3)]
+    pub warmup: u32,
+    /// Stop sampling a stage after this many seconds
+    #[arg(long, default_value_t = 10.0)]
+    pub max_time: f64,
+    /// Baseline file to compare against
+    #[arg(long, default_value = BASELINE_FILE)]
+    pub baseline: String,
+    /// Save this run as the new baseline
+    #[arg(long)]
+    pub save: bool,
+}
+
+impl BenchArgs {
+    pub fn options(&self) -> BenchOptions {
+        BenchOptions {
+            part: self.part.and_then(Part::from_number),
+            warmup: self.warmup,
+            samples: self.samples,
+            max_time: Duration::from_secs_f64(self.max_time.max(0.0)),
+        }
+    }
+}
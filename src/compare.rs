@@ -0,0 +1,83 @@
+use std::time::Duration;
+use crate::answer::Answer;
+use crate::bench;
+use crate::day::{InputKind, Part, PartResult};
+use crate::error::Result;
+
+// Everything one implementation of a day returned
+pub struct ImplementationRun {
+    pub name: &'static str,
+    pub result: Result<Vec<PartResult>>,
+}
+
+fn label(input: &InputKind, part: Part) -> String {
+    match input {
+        InputKind::Example(name) => format!("Example {} - Part {}", name, part.number()),
+        InputKind::Full => format!("Full - Part {}", part.number()),
+    }
+}
+
+// Lines up each input and part across the implementations and ranks them by time.
+// Returns false when they don't all agree or one of them failed.
+pub fn print_comparison(day: u8, title: &str, runs: &[ImplementationRun]) -> bool {
+    println!("<--------    Comparing Day {}: {}    -------->", day, title);
+
+    let mut agreed = true;
+    for run in runs.iter() {
+        if let Err(e) = &run.result {
+            println!("{}: Error: {}", run.name, e);
+            agreed = false;
+        }
+    }
+
+    // Inputs and parts in the order the first implementation to get through them ran them
+    let mut keys: Vec<(InputKind, Part)> = Vec::new();
+    for results in runs.iter().filter_map(|x| x.result.as_ref().ok()) {
+        for result in results {
+            if !keys.contains(&(result.input.clone(), result.part)) {
+                keys.push((result.input.clone(), result.part));
+            }
+        }
+    }
+
+    for (input, part) in keys {
+        println!("{}", label(&input, part));
+        let answers: Vec<(&str, &Result<Answer>, Duration)> = runs
+            .iter()
+            .filter_map(|run| {
+                let results = run.result.as_ref().ok()?;
+                let result = results.iter().find(|x| x.input == input && x.part == part)?;
+                Some((run.name, &result.answer, result.elapsed))
+            })
+            .collect();
+        // Parts that ran out of time aren't in the race
+        let fastest = answers.iter().filter(|x| x.1.is_ok()).map(|x| x.2).min().unwrap_or_default();
+
+        for (name, answer, elapsed) in answers.iter() {
+            let answer = match answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    println!("    {:<14} {}", name, e);
+                    agreed = false;
+                    continue;
+                }
+            };
+            let speed = match *elapsed == fastest {
+                true => "fastest".to_string(),
+                false => bench::speed_change(fastest, *elapsed),
+            };
+            println!("    {:<14} {:>20} {:>11}   {}", name, answer.one_line(), format!("{:.2?}", elapsed), speed);
+        }
+
+        let mut finished = answers.iter().filter_map(|x| x.1.as_ref().ok());
+        if let Some(first) = finished.next() {
+            if finished.any(|x| x != first) {
+                println!("Mismatch! The implementations disagree\x07");
+                agreed = false;
+            }
+        }
+        println!();
+    }
+
+    agreed
+}
@@ -1,122 +1,300 @@
-﻿use std::fs::{self, read_to_string};
+﻿use std::time::{Duration, Instant};
+use crate::answer::Answer;
+use crate::bench::{BenchOptions, Stage, Stats};
+use crate::cancel::{self, CancelToken};
+use crate::error::{Error, Result};
+use crate::input::InputSource;
+use crate::memory::{self, MemoryStats};
+use crate::output::Reporter;
+use crate::visualize::Frame;
 
-pub trait Day<TData> {
-    fn parse_file(&self, file_content: String) -> TData;
+pub trait Day {
+    type Input;
 
-    fn part_1(&self, data: &TData) -> i64;
+    fn parse_file(&self, file_content: String) -> Result<Self::Input>;
 
-    fn part_2(&self, data: &TData) -> i64;
+    // Days whose part 2 reads the input differently parse it again here,
+    // None reuses the data from parse_file
+    fn parse_part_2(&self, _file_content: &str) -> Option<Result<Self::Input>> {
+        None
+    }
+
+    fn part_1(&self, data: &Self::Input) -> Result<Answer>;
+
+    fn part_2(&self, data: &Self::Input) -> Result<Answer>;
+
+    // Snapshots of the solver's state for --visualize, None for days without one
+    fn visualize(&self, _data: &Self::Input) -> Option<Result<Vec<Frame>>> {
+        None
+    }
 }
 
-pub fn run_with_test<TData, TDay: Day<TData>>(
-    day: &TDay,
-    example_file: &str,
-    example_expected: (i64, i64),
-    full_file: &str,
-) -> std::io::Result<()> {
-    println!("Testing with example dataset: ");
-    let example_actual = run(day, example_file)?;
-    if example_actual != example_expected {
-        println!("Error! Expected answer\n\"Part 1: {}, Part 2: {}\", but got\n\"Part 1: {}, Part 2: {}\"\x07", 
-                 example_expected.0, example_expected.1,
-                 example_actual.0, example_actual.1);
+// Object safe view of a Day with its input type hidden, so the registry can hold any day
+pub trait Solution {
+    fn run(&self, inputs: &Inputs, options: &RunOptions, reporter: &mut dyn Reporter) -> Result<Vec<PartResult>>;
 
-        return Ok(());
+    fn bench(&self, file_content: &str, options: &BenchOptions) -> Result<Vec<(Stage, Stats)>>;
+
+    fn solve(&self, file_content: &str, part: Part) -> Result<Answer>;
+
+    fn visualize(&self, file_content: &str) -> Result<Option<Vec<Frame>>>;
+}
+
+impl<TDay: Day> Solution for TDay {
+    fn run(&self, inputs: &Inputs, options: &RunOptions, reporter: &mut dyn Reporter) -> Result<Vec<PartResult>> {
+        run(self, inputs, options, reporter)
     }
 
-    println!("Example Successful! Moving to full dataset:");
-    let _ = run(day, full_file)?;
+    fn bench(&self, file_content: &str, options: &BenchOptions) -> Result<Vec<(Stage, Stats)>> {
+        crate::bench::bench(self, file_content, options)
+    }
 
-    print!("\x07");
+    fn solve(&self, file_content: &str, part: Part) -> Result<Answer> {
+        solve(self, file_content, part)
+    }
 
-    Ok(())
+    fn visualize(&self, file_content: &str) -> Result<Option<Vec<Frame>>> {
+        Day::visualize(self, &self.parse_file(file_content.to_string())?).transpose()
+    }
 }
 
-pub fn run_with_test_2<TData, TDay: Day<TData>>(
-    day: &TDay,
-    example_file: &str,
-    example_expected: (i64, i64),
-    full_file: &str,
-) -> std::io::Result<()> {
-    let file_content_example = read_to_string(example_file)?;
-    let file_content_full = read_to_string(full_file)?;
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    // None selects both parts
+    pub fn is_selected(self, selected: Option<Part>) -> bool {
+        selected.is_none_or(|part| part == self)
+    }
+}
 
-    print!("Example - ");
-    let file_data_example = test_parse_data(day, file_content_example);
-    print!("Full - ");
-    let file_data_full = test_parse_data(day, file_content_full);
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum InputKind {
+    // Named after its file, e.g. day16e
+    Example(String),
+    Full,
+}
 
-    print!("Example - ");
-    let part_1_example = test_part_1(day, &file_data_example);
-    if part_1_example != example_expected.0 {
-        println!(
-            "Error! Expected answer\n\"Part 1: {}\", but got\n\"Part 1: {}\"\x07",
-            example_expected.0, part_1_example
-        );
-        return Ok(());
+impl InputKind {
+    pub fn is_example(&self) -> bool {
+        matches!(self, InputKind::Example(_))
     }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExampleInput {
+    pub name: String,
+    pub source: InputSource,
+    // None for parts without a known example answer
+    pub expected: (Option<Answer>, Option<Answer>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Inputs {
+    pub examples: Vec<ExampleInput>,
+    pub full: InputSource,
+    // Answers recorded in the manifest, if any
+    pub full_expected: (Option<Answer>, Option<Answer>),
+}
 
-    print!("Actual - ");
-    let _ = test_part_1(day, &file_data_full);
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    // None runs both parts
+    pub part: Option<Part>,
+    pub run_example: bool,
+    pub run_full: bool,
+    // Skips everything after the first wrong example answer, full input regressions never stop
+    pub stop_on_mismatch: bool,
+    // Time budget for each part on each input, None lets parts run as long as they need
+    pub timeout: Option<Duration>,
+}
 
-    print!("Example - ");
-    let part_2_example = test_part_2(day, &file_data_example);
-    if part_2_example != example_expected.1 {
-        println!(
-            "Error! Expected answer\n\"Part 2: {}\", but got\n\"Part 2: {}\"\x07",
-            example_expected.1, part_2_example
-        );
-        return Ok(());
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions {
+            part: None,
+            run_example: true,
+            run_full: true,
+            stop_on_mismatch: true,
+            timeout: None,
+        }
     }
+}
 
-    print!("Actual - ");
-    let _ = test_part_2(day, &file_data_full);
+#[derive(Clone, Debug)]
+pub struct PartResult {
+    pub input: InputKind,
+    pub part: Part,
+    // Err when the part ran out of time, the other parts and inputs still run
+    pub answer: Result<Answer>,
+    pub expected: Option<Answer>,
+    pub elapsed: Duration,
+    // Only measured when built with the count-alloc feature
+    pub memory: Option<MemoryStats>,
+}
 
-    print!("\x07");
+impl PartResult {
+    // None when there was nothing to check against, a part that ran out of time never passes
+    pub fn passed(&self) -> Option<bool> {
+        match &self.answer {
+            Ok(answer) => self.expected.as_ref().map(|x| x == answer),
+            Err(_) => Some(false),
+        }
+    }
+}
 
-    Ok(())
+// Parsed input for each part, part 2 only has its own when the day parses it separately
+struct ParsedInput<TData> {
+    kind: InputKind,
+    data: TData,
+    part_2_data: Option<TData>,
+    expected: (Option<Answer>, Option<Answer>),
 }
 
-fn test_part_1<TData, TDay: Day<TData>>(day: &TDay, data: &TData) -> i64 {
-    let now = std::time::Instant::now();
-    let part_1 = day.part_1(data);
-    let elapsed = now.elapsed();
-    println!("Part 1: {}", part_1);
-    println!("Elapsed Time: {:.2?}", elapsed);
-    println!();
+impl<TData> ParsedInput<TData> {
+    fn data(&self, part: Part) -> &TData {
+        match part {
+            Part::One => &self.data,
+            Part::Two => self.part_2_data.as_ref().unwrap_or(&self.data),
+        }
+    }
 
-    part_1
+    fn expected(&self, part: Part) -> Option<Answer> {
+        match part {
+            Part::One => self.expected.0.clone(),
+            Part::Two => self.expected.1.clone(),
+        }
+    }
 }
 
-fn test_part_2<TData, TDay: Day<TData>>(day: &TDay, data: &TData) -> i64 {
-    let now = std::time::Instant::now();
-    let part_2 = day.part_2(data);
-    let elapsed = now.elapsed();
-    println!("Part 2: {}", part_2);
-    println!("Elapsed Time: {:.2?}", elapsed);
-    println!();
+// Parses every selected input first, then runs each part on the examples before the full input
+pub fn run<TDay: Day + ?Sized>(
+    day: &TDay,
+    inputs: &Inputs,
+    options: &RunOptions,
+    reporter: &mut dyn Reporter,
+) -> Result<Vec<PartResult>> {
+    let mut parsed = Vec::new();
+    if options.run_example {
+        for example in inputs.examples.iter() {
+            let file_content = example.source.read()?;
+            let kind = InputKind::Example(example.name.clone());
+            parsed.push(parse(day, kind, file_content, example.expected.clone(), options.part, reporter)?);
+        }
+    }
+    if options.run_full {
+        let file_content = inputs.full.read()?;
+        let expected = inputs.full_expected.clone();
+        parsed.push(parse(day, InputKind::Full, file_content, expected, options.part, reporter)?);
+    }
+
+    let mut results = Vec::new();
+    for part in Part::ALL.into_iter().filter(|x| x.is_selected(options.part)) {
+        for input in parsed.iter() {
+            let result = solve_part(day, part, input, options.timeout, reporter)?;
+            let mismatch = result.input.is_example() && result.answer.is_ok() && result.passed() == Some(false);
+            results.push(result);
 
-    part_2
+            if mismatch && options.stop_on_mismatch {
+                return Ok(results);
+            }
+        }
+    }
+
+    Ok(results)
 }
 
-fn test_parse_data<TData, TDay: Day<TData>>(day: &TDay, file_content: String) -> TData {
-    let now = std::time::Instant::now();
-    let file_data = day.parse_file(file_content);
-    let elapsed = now.elapsed();
-    println!("Parsed file.");
-    println!("Elapsed Time: {:.2?}", elapsed);
-    println!();
+fn parse<TDay: Day + ?Sized>(
+    day: &TDay,
+    kind: InputKind,
+    file_content: String,
+    expected: (Option<Answer>, Option<Answer>),
+    part: Option<Part>,
+    reporter: &mut dyn Reporter,
+) -> Result<ParsedInput<TDay::Input>> {
+    let (parsed, memory) = memory::measure(|| {
+        let now = Instant::now();
+        let part_2_data = match Part::Two.is_selected(part) {
+            true => day.parse_part_2(&file_content).transpose()?,
+            false => None,
+        };
+        let data = day.parse_file(file_content)?;
+        Ok((data, part_2_data, now.elapsed()))
+    });
+    let (data, part_2_data, elapsed) = parsed?;
+    reporter.parsed(&kind, elapsed, memory);
 
-    file_data
+    Ok(ParsedInput {
+        kind,
+        data,
+        part_2_data,
+        expected,
+    })
 }
 
-pub fn run<TData, TDay: Day<TData>>(day: &TDay, file_name: &str) -> std::io::Result<(i64, i64)> {
-    let file_content = fs::read_to_string(file_name)?;
+fn solve_part<TDay: Day + ?Sized>(
+    day: &TDay,
+    part: Part,
+    input: &ParsedInput<TDay::Input>,
+    timeout: Option<Duration>,
+    reporter: &mut dyn Reporter,
+) -> Result<PartResult> {
+    let ((answer, elapsed), memory) = memory::measure(|| {
+        let now = Instant::now();
+        let answer = cancel::scope(CancelToken::with_timeout(timeout), || match part {
+            Part::One => day.part_1(input.data(part)),
+            Part::Two => day.part_2(input.data(part)),
+        });
+        (answer, now.elapsed())
+    });
+    let answer = match answer.map_err(|e| e.with_part(part)) {
+        Err(e @ Error::Cancelled { .. }) => Err(e),
+        Err(e) => return Err(e),
+        Ok(answer) => Ok(answer),
+    };
 
-    let file_data = test_parse_data(day, file_content);
+    let result = PartResult {
+        input: input.kind.clone(),
+        part,
+        answer,
+        expected: input.expected(part),
+        elapsed,
+        memory,
+    };
+    reporter.solved(&result);
+
+    Ok(result)
+}
 
-    let part_1 = test_part_1(day, &file_data);
-    let part_2 = test_part_2(day, &file_data);
+// Parses and solves a single part without any timing or reporting
+pub fn solve<TDay: Day + ?Sized>(day: &TDay, file_content: &str, part: Part) -> Result<Answer> {
+    let answer = match part {
+        Part::One => day.part_1(&day.parse_file(file_content.to_string())?),
+        Part::Two => match day.parse_part_2(file_content) {
+            Some(data) => day.part_2(&data?),
+            None => day.part_2(&day.parse_file(file_content.to_string())?),
+        },
+    };
 
-    Ok((part_1, part_2))
+    answer.map_err(|e| e.with_part(part))
 }
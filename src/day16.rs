@@ -1,30 +1,120 @@
-﻿use std::collections::HashMap;
-// use crate::day;
+﻿// use crate::day;
+use crate::answer::Answer;
+use crate::cancel;
 use crate::day::Day;
+use crate::error::{Error, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 pub struct Day16;
 
-impl Day<ValveInfo> for Day16 {
-    fn parse_file(&self, file_content: String) -> ValveInfo {
+impl Day for Day16 {
+    type Input = ValveInfo;
+
+    fn parse_file(&self, file_content: String) -> Result<ValveInfo> {
+        parse::create_valve_info(file_content)
+    }
+
+    fn part_1(&self, data: &ValveInfo) -> Result<Answer> {
+        data.check()?;
+        let result = part_1::part_1(part_1::State::new(), data);
+        cancel::check()?;
+        Ok(result.into())
+    }
+
+    fn part_2(&self, data: &ValveInfo) -> Result<Answer> {
+        data.check()?;
+        let mut best = 0;
+        let result = part_2::part_2(part_2::State::new(), data, &mut best);
+        cancel::check()?;
+        Ok(result.into())
+    }
+}
+
+// Finds the best pressure for every set of opened valves in one search, then picks the best
+// set for part 1 and the best pair of disjoint sets for part 2
+pub struct Day16Bitmask;
+
+impl Day for Day16Bitmask {
+    type Input = ValveInfo;
+
+    fn parse_file(&self, file_content: String) -> Result<ValveInfo> {
         parse::create_valve_info(file_content)
     }
 
-    fn part_1(&self, data: &ValveInfo) -> i64 {
-        part_1::part_1(part_1::State::new(), data) as i64
+    fn part_1(&self, data: &ValveInfo) -> Result<Answer> {
+        data.check()?;
+        let best = bitmask::best_per_set(data, 0);
+        cancel::check()?;
+        Ok(best.iter().max().copied().unwrap_or(0).into())
     }
 
-    fn part_2(&self, data: &ValveInfo) -> i64 {
-        let mut best_with_valves = HashMap::new();
-        part_2::part_2(part_2::State::new(), data, &mut best_with_valves) as i64
+    fn part_2(&self, data: &ValveInfo) -> Result<Answer> {
+        data.check()?;
+        // Teaching the elephant takes 4 of the 30 minutes
+        let best = bitmask::best_per_set(data, 4);
+        cancel::check()?;
+        Ok(bitmask::best_disjoint_pair(&best, data.valves.len()).into())
     }
 }
 
+// size valves, at most 15 of them with a flow rate like the real input, joined by a random
+// spanning tree with some extra tunnels so there's more than one way around
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    // Two letter names, and the start needs somewhere to go
+    let size = size.clamp(2, 128);
+    let mut names = vec!["AA".to_string()];
+    while names.len() < size {
+        let name: String = (0..2).map(|_| rng.gen_range(b'A'..=b'Z') as char).collect();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); size];
+    let connect = |a: usize, b: usize, tunnels: &mut Vec<Vec<usize>>| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for i in 1..size {
+        connect(i, rng.gen_range(0..i), &mut tunnels);
+    }
+    for _ in 0..size / 2 {
+        connect(rng.gen_range(0..size), rng.gen_range(0..size), &mut tunnels);
+    }
+
+    // The start never has a flow rate
+    let mut flows = vec![0; size];
+    let mut candidates: Vec<usize> = (1..size).collect();
+    candidates.shuffle(rng);
+    for i in candidates.into_iter().take((size / 4).clamp(1, 15)) {
+        flows[i] = rng.gen_range(1..=25);
+    }
+
+    let mut lines: Vec<String> = (0..size)
+        .map(|i| {
+            let leads_to: Vec<&str> = tunnels[i].iter().map(|x| names[*x].as_str()).collect();
+            match leads_to.len() {
+                1 => format!("Valve {} has flow rate={}; tunnel leads to valve {}", names[i], flows[i], leads_to[0]),
+                _ => format!("Valve {} has flow rate={}; tunnels lead to valves {}", names[i], flows[i], leads_to.join(", ")),
+            }
+        })
+        .collect();
+    lines.shuffle(rng);
+
+    lines.iter().map(|x| format!("{}\n", x)).collect()
+}
 
 mod parse {
+    use std::collections::HashSet;
     use crate::day16::{Valve, ValveInfo};
+    use crate::error::{Error, Result};
 
-    pub fn create_valve_info(file: String) -> ValveInfo {
-        let info = parse_file_to_info(file);
+    pub fn create_valve_info(file: String) -> Result<ValveInfo> {
+        let info = parse_file_to_info(file)?;
 
         let valve_connections = create_graph(&info);  // create the graph with time b/w valves
         let limit = info.limit;
@@ -35,11 +125,11 @@ mod parse {
                 || x.valve_id == 0                  // start position
         });
         // Unoptimized struct for each valve
-        ValveInfo {
+        Ok(ValveInfo {
             valve_connections,
             valves,
             limit
-        }
+        })
     }
 
     struct Info {
@@ -49,32 +139,53 @@ mod parse {
     }
 
     // Parses the file
-    fn parse_file_to_info(file: String) -> Info {
-        let mut file_content = file;
+    fn parse_file_to_info(file: String) -> Result<Info> {
+        // get rid of unneeded info in each line, keeping the line number and text for errors
+        let mut lines: Vec<(usize, &str, String)> = Vec::new();
+        for (i, line) in file.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let inter = raw_line_to_inter(line).ok_or_else(|| Error::parse(
+                i + 1,
+                line,
+                "expected \"Valve XX has flow rate=N; tunnels lead to valves YY, ZZ\"",
+            ))?;
+            lines.push((i + 1, line, inter));
+        }
+        if lines.len() > 128 {
+            let (line_number, line, _) = lines[128];
+            return Err(Error::parse(line_number, line, "at most 128 valves are supported"));
+        }
 
         // Code assumed the first line in the file was the starting point when it's actually Valve AA
         // More work to rewrite the entire program so I just swap the lines
-        let mut aa_line_index = 0;
-        for (i, line) in file_content.lines().enumerate() {
-            if &line[..8] == "Valve AA" {
-                aa_line_index = i;
-                break;
+        let aa_line_index = lines.iter()
+            .position(|(_, _, x)| x.starts_with("AA "))
+            .ok_or_else(|| Error::parse(1, file.lines().next().unwrap_or(""), "no line for the starting valve AA"))?;
+        lines.swap(0, aa_line_index);
+
+        // Every tunnel has to lead to a valve with its own line
+        let names: HashSet<&str> = lines.iter().map(|(_, _, x)| &x[..2]).collect();
+        for (line_number, line, inter) in lines.iter() {
+            if let Some(unknown) = inter.split(' ').skip(2).find(|x| !names.contains(x)) {
+                return Err(Error::parse(*line_number, line, format!("tunnel leads to unknown valve {}", unknown)));
             }
         }
-        file_content = swap_lines(&file_content, 0, aa_line_index);
 
+        let mut file_content = lines.iter().map(|(_, _, x)| x.as_str()).collect::<Vec<_>>().join("\n");
 
-        // get rid of unneeded info in each line
-        let lines: Vec<String> = file_content.lines().map(raw_line_to_inter).collect();
-        file_content = lines.join("\n");
-        // println!("{:.2?}", now.elapsed());
-        
         // Find and replace valve id's with numbers
-        for (i, line) in lines.iter().enumerate() {
+        for (i, (_, _, line)) in lines.iter().enumerate() {
             file_content = file_content.replace(&line[..2], &i.to_string());
         }
 
-        let valves: Vec<Valve> = file_content.split("\n").map(num_line_to_valve).collect();
+        let mut valves = Vec::with_capacity(lines.len());
+        for (num_line, (line_number, line, _)) in file_content.split('\n').zip(lines.iter()) {
+            let valve = num_line_to_valve(num_line)
+                .ok_or_else(|| Error::parse(*line_number, line, "flow rate is too large"))?;
+            valves.push(valve);
+        }
 
         let mut usable_valves = 0;
         for (i, valve) in valves.iter().enumerate() {
@@ -83,55 +194,41 @@ mod parse {
             }
         }
 
-        Info {
+        Ok(Info {
             valves,
             usable_valves,
             limit: 30
-        }
-    }
-
-    fn swap_lines(string: &str, i: usize, n: usize) -> String {
-        let mut lines: Vec<&str> = string.lines().collect();
-
-        if i >= lines.len() || n >= lines.len() {
-            return String::from(string); // Return original string if line indices are out of bounds
-        }
-
-        let temp = lines[i];
-        lines[i] = lines[n];
-        lines[n] = temp;
-
-        lines.join("\n")
+        })
     }
 
     // Removes the surrounding stuff
     // "Valve FY has flow rate=17; tunnels lead to valves GG, KJ"
     // to "FY 17 GG KJ"
-    fn raw_line_to_inter(str: &str) -> String {
+    fn raw_line_to_inter(str: &str) -> Option<String> {
 
         lazy_static::lazy_static! {
             static ref RE: regex::Regex =
-                regex::Regex::new(r"Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (.+)").unwrap();
+                regex::Regex::new(r"Valve ([A-Z]{2}) has flow rate=(\d+); tunnels? leads? to valves? ([A-Z]{2}(?:, [A-Z]{2})*)$").unwrap();
         }
 
-        let captures = RE.captures(str).expect("Regex pattern didn't match");
+        let captures = RE.captures(str)?;
 
-        let valve = captures.get(1).unwrap().as_str();
-        let flow = captures.get(2).unwrap().as_str();
-        let connections = captures.get(3).unwrap().as_str().replace(", ", " ");
+        let valve = captures.get(1)?.as_str();
+        let flow = captures.get(2)?.as_str();
+        let connections = captures.get(3)?.as_str().replace(", ", " ");
 
-        format!("{} {} {}", valve, flow, connections)
+        Some(format!("{} {} {}", valve, flow, connections))
     }
 
     // Parses a num line to a Valve
     // "4 20 44 32" becomes a Valve with 20 flow and connections to valves 44 and 32
-    fn num_line_to_valve(str: &str) -> Valve {
-        let spl: Vec<&str> = str.split(" ").collect();
-        Valve {
-            valve_id: spl[0].to_string().parse().unwrap(),
-            flow: spl[1].to_string().parse().unwrap(),
-            connections: spl[2..].iter().map(|x| x.to_string().parse().unwrap()).collect(),
-        }
+    fn num_line_to_valve(str: &str) -> Option<Valve> {
+        let spl: Vec<&str> = str.split(' ').collect();
+        Some(Valve {
+            valve_id: spl[0].parse().ok()?,
+            flow: spl[1].parse().ok()?,
+            connections: spl[2..].iter().map(|x| x.parse().ok()).collect::<Option<_>>()?,
+        })
     }
 
     fn create_graph(info: &Info) -> Vec<Vec<u8>> {
@@ -183,7 +280,7 @@ mod parse {
 }
 
 mod part_2 {
-    use std::collections::HashMap;
+    use crate::cancel;
     use crate::day16::ValveInfo;
 
     pub struct State {
@@ -226,6 +323,40 @@ mod part_2 {
                 && self.time + info.get_move_cost(self.e_location, valve_id) + 1 < info.limit
         }
 
+        // As if whoever gets to each closed valve first opened it, which is never less than what's
+        // really left
+        fn most_left_to_gain(&self, info: &ValveInfo) -> u16 {
+            let player = (self.p_location, self.time + self.p_timer);
+            let elephant = (self.e_location, self.time + self.e_timer);
+            (1..info.valves.len() as u8)
+                .filter(|x| !self.has_valve_open(*x))
+                .map(|valve_id| {
+                    [player, elephant]
+                        .iter()
+                        .map(|(location, free_at)| free_at + info.get_move_cost(*location, valve_id))
+                        .filter(|arrival| arrival + 1 < info.limit)
+                        .map(|arrival| info.get_total_pressure_at_time(arrival, valve_id))
+                        .max()
+                        .unwrap_or(0)
+                })
+                .sum()
+        }
+
+        // Busy until time runs out, so only the other one opens valves from here on
+        pub fn stop_player(&self, info: &ValveInfo) -> State {
+            State {
+                p_timer: info.limit - self.time,
+                ..*self
+            }
+        }
+
+        pub fn stop_elephant(&self, info: &ValveInfo) -> State {
+            State {
+                e_timer: info.limit - self.time,
+                ..*self
+            }
+        }
+
         pub fn wait(&self) -> State {  // need to wait one minute when opening a valve
             State {
                 time: self.time + 1,
@@ -288,13 +419,16 @@ mod part_2 {
     pub fn part_2(
         state: State,
         info: &ValveInfo,
-        best_with_valves: &mut HashMap<u16, u16>
+        best: &mut u16
     ) -> u16 {
-        
-        if let Some(pressure) = best_with_valves.get(&state.pressure).copied() {
-            if state.pressure < pressure {
-                return state.pressure;
-            }
+        if cancel::is_cancelled() {
+            return 0;
+        }
+
+        // Also ends the search once neither of them can open anything else
+        *best = (*best).max(state.pressure);
+        if state.pressure + state.most_left_to_gain(info) <= *best {
+            return state.pressure;
         }
 
         let mut result = state.pressure;
@@ -302,7 +436,6 @@ mod part_2 {
         match (state.p_timer, state.e_timer) {
             (0, 0) => {
                 // Have both player and elephant move
-                let mut both_moved = false;
                 for i in 1..info.valves.len() {  // player destinations
                     let i = i as u8;
                     if !state.player_can_move(info, i) {
@@ -314,30 +447,12 @@ mod part_2 {
                             continue;
                         }
 
-                        both_moved = true;
-                        result = result.max(part_2(state.move_player_and_elephant(info, i, j), info, best_with_valves));
-                    }
-                }
-                // what if player/elephant can't move but elephant/player can?
-                if !both_moved {
-                    // Have only player move
-                    for i in 1..info.valves.len() {
-                        let i = i as u8;
-
-                        if state.player_can_move(info, i) {
-                            result = result.max(part_2(state.move_player(info, i), info, best_with_valves));
-                        }
-                    }
-
-                    // Have only elephant move
-                    for i in 1..info.valves.len() {
-                        let i = i as u8;
-
-                        if state.elephant_can_move(info, i) {
-                            result = result.max(part_2(state.move_elephant(info, i), info, best_with_valves));
-                        }
+                        result = result.max(part_2(state.move_player_and_elephant(info, i, j), info, best));
                     }
                 }
+                // Or leave the rest to one of them
+                result = result.max(part_2(state.stop_elephant(info), info, best));
+                result = result.max(part_2(state.stop_player(info), info, best));
             },
             (0, _) => {
                 // Have only player move
@@ -346,9 +461,11 @@ mod part_2 {
                     let i = i as u8;
 
                     if state.player_can_move(info, i) {
-                        result = result.max(part_2(state.move_player(info, i), info, best_with_valves));
+                        result = result.max(part_2(state.move_player(info, i), info, best));
                     }
                 }
+                // Or leave the rest to the elephant
+                result = result.max(part_2(state.stop_player(info), info, best));
             },
             (_, 0) => {
                 // Have only elephant move
@@ -356,22 +473,16 @@ mod part_2 {
                     let i = i as u8;
 
                     if state.elephant_can_move(info, i) {
-                        result = result.max(part_2(state.move_elephant(info, i), info, best_with_valves));
+                        result = result.max(part_2(state.move_elephant(info, i), info, best));
                     }
                 }
+                // Or leave the rest to the player
+                result = result.max(part_2(state.stop_elephant(info), info, best));
             },
             (_, _) => {
-                result = result.max(part_2(state.wait(), info, best_with_valves));
+                result = result.max(part_2(state.wait(), info, best));
             }
         };
-        
-        best_with_valves.entry(state.open_valves)
-            .and_modify(|v| {
-                if result > *v {
-                    *v = result;
-                }
-            })
-            .or_insert(result);
 
         result
     }
@@ -379,6 +490,7 @@ mod part_2 {
 
 
     mod part_1 {
+        use crate::cancel;
         use crate::day16::ValveInfo;
     pub struct State {
         location: u8,       // current valve location
@@ -423,6 +535,9 @@ mod part_2 {
     }
 
     pub fn part_1(state: State, info: &ValveInfo) -> u16 {
+        if cancel::is_cancelled() {
+            return 0;
+        }
 
         let mut result = state.pressure;
 
@@ -440,6 +555,59 @@ mod part_2 {
     }
 }
 
+mod bitmask {
+    use crate::cancel;
+    use crate::day16::ValveInfo;
+
+    // Indexed by the bit mask of opened valves, 0 for sets that can't be opened in time
+    pub fn best_per_set(info: &ValveInfo, start_time: u8) -> Vec<u16> {
+        let mut best = vec![0; 1 << info.valves.len()];
+        visit(info, 0, start_time, 0, 0, &mut best);
+        best
+    }
+
+    fn visit(info: &ValveInfo, location: u8, time: u8, open_valves: usize, pressure: u16, best: &mut [u16]) {
+        if cancel::is_cancelled() {
+            return;
+        }
+
+        best[open_valves] = best[open_valves].max(pressure);
+        for i in 1..info.valves.len() {
+            if open_valves >> i & 1 == 1 {
+                continue;
+            }
+
+            let valve_id = i as u8;
+            let arrival = time + info.get_move_cost(location, valve_id);
+            // +1 because opening the valve takes a minute
+            if arrival + 1 >= info.limit {
+                continue;
+            }
+            let pressure = pressure + info.get_total_pressure_at_time(arrival, valve_id);
+            visit(info, valve_id, arrival + 1, open_valves | 1 << i, pressure, best);
+        }
+    }
+
+    // The player and the elephant open disjoint sets of valves
+    pub fn best_disjoint_pair(best: &[u16], num_valves: usize) -> u16 {
+        // best_subset[mask] is the best of any set inside mask
+        let mut best_subset = best.to_vec();
+        for bit in 0..num_valves {
+            for mask in 0..best_subset.len() {
+                if mask >> bit & 1 == 1 {
+                    best_subset[mask] = best_subset[mask].max(best_subset[mask ^ 1 << bit]);
+                }
+            }
+        }
+
+        let all_valves = best.len() - 1;
+        (0..best.len())
+            .map(|mask| best[mask] + best_subset[all_valves ^ mask])
+            .max()
+            .unwrap_or(0)
+    }
+}
+
 struct Valve {
     valve_id: u8,
     flow: u16,
@@ -453,9 +621,24 @@ pub struct ValveInfo {
 }
 
 impl ValveInfo {
+    // Open valves are tracked in a u16 bit mask, start valve included. Move costs are added to
+    // the time as u8, so a u8::MAX cost between valves that can't reach each other would overflow.
+    fn check(&self) -> Result<()> {
+        if self.valves.len() > 16 {
+            return Err(Error::solve(format!(
+                "{} valves have a flow rate, at most 15 are supported",
+                self.valves.len() - 1
+            )));
+        }
+        if self.valve_connections.iter().any(|x| x.contains(&u8::MAX)) {
+            return Err(Error::solve("some valves with a flow rate can't be reached from AA or from each other"));
+        }
+        Ok(())
+    }
+
     #[inline]
     fn get_move_cost(&self, from: u8, to: u8) -> u8 {
-        unsafe { self.valve_connections.get_unchecked(from as usize).get_unchecked(to as usize).clone() }
+        unsafe { *self.valve_connections.get_unchecked(from as usize).get_unchecked(to as usize) }
     }
 
     #[inline]
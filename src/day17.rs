@@ -2,7 +2,13 @@
 use cgmath::Vector2;
 use crate::day17::Jet::{Left, Right};
 use crate::day17::Rock::{IHor, IVert, L, O, X};
+use crate::answer::Answer;
+use crate::cancel;
 use crate::day::Day;
+use crate::error::{Error, Result};
+use crate::visualize::Frame;
+use rand::rngs::StdRng;
+use rand::Rng;
 
 pub struct Day17;
 
@@ -56,7 +62,7 @@ const O_ARR: [Vector2<u64>; 4] = [
     Vector2 {x: 1, y: 1},
 ];
 
-fn rock_to_squares(rock: &Rock) -> core::slice::Iter<Vector2<u64>> {
+fn rock_to_squares(rock: &Rock) -> core::slice::Iter<'_, Vector2<u64>> {
     match rock {
         Rock::IHor => IHOR_ARR.iter(),
         Rock::X => X_ARR.iter(),
@@ -70,71 +76,41 @@ pub struct Info {
     jet_pattern: Vec<Jet>
 }
 
-impl Day<Info> for Day17 {
-    fn parse_file(&self, file_content: String) -> Info {
-        Info {
-            jet_pattern: file_content.chars().map(|x| match x {
-                '>' => Right,
-                '<' => Left,
-                _ => panic!("{}", x as u8)
-            }).collect()
-        }
-    }
-
-    fn part_1(&self, data: &Info) -> i64 {
-        
-        const ROCK_LIMIT: u64 = 2022;
-
-        let mut blocks_set: HashSet<Vector2<u64>> = HashSet::new();
-        let mut y_max = 0;
-
-        let rocks = [IHor, X, L, IVert, O];
-        let mut rocks = rocks.iter().cycle();
-
-        let mut pos = Vector2 {x: 2, y: y_max + 4};
-        let mut rock = rocks.next().unwrap();
-        let mut rock_count = 0;
-        for jet in data.jet_pattern.iter().cycle() {
+impl Day for Day17 {
+    type Input = Info;
 
-            match jet {
-                Left => {
-                    if pos.x != 0 {
-                        pos.x -= 1;
-                        if intersects(pos, rock, &mut blocks_set) {
-                            pos.x += 1;
-                        }
-                    }
-                }
-                Right => {
-                    if pos.x != 6 {
-                        pos.x += 1;
-                        if intersects(pos, rock, &mut blocks_set) {
-                            pos.x -= 1;
-                        }
-                    }
-                }
+    fn parse_file(&self, file_content: String) -> Result<Info> {
+        let mut jet_pattern = Vec::new();
+        for (i, line) in file_content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if !jet_pattern.is_empty() {
+                return Err(Error::parse(i + 1, line, "expected the jet pattern on a single line"));
             }
 
-            pos.y -= 1;
-            if intersects(pos, rock, &mut blocks_set) || pos.y == 0 {
-                pos.y += 1;
-                rock_count += 1;
-
-                y_max = insert_rock(pos, rock, y_max, &mut blocks_set);
-
-                if rock_count == ROCK_LIMIT {
-                    break;
-                }
-
-                rock = rocks.next().unwrap();
-                pos = Vector2 {x: 2, y: y_max + 4};
+            for x in line.chars() {
+                jet_pattern.push(match x {
+                    '>' => Right,
+                    '<' => Left,
+                    _ => return Err(Error::parse(i + 1, line, format!("unexpected character {:?} in jet pattern", x))),
+                });
             }
         }
 
-        y_max as i64
+        if jet_pattern.is_empty() {
+            return Err(Error::parse(1, &file_content, "expected a jet pattern of '<' and '>'"));
+        }
+
+        Ok(Info { jet_pattern })
     }
 
-    fn part_2(&self, data: &Info) -> i64 {
+    fn part_1(&self, data: &Info) -> Result<Answer> {
+        Ok(drop_rocks(data, 2022, |_, _| {})?.into())
+    }
+
+    fn part_2(&self, data: &Info) -> Result<Answer> {
         const ROCK_LIMIT: u64 = 1000000000000;
 
         let mut blocks_set: HashSet<Vector2<u64>> = HashSet::new();
@@ -159,6 +135,7 @@ impl Day<Info> for Day17 {
         let mut y_before = 0;
         let mut rocks_before = 0;
         for jet in data.jet_pattern.iter().cycle() {
+            cancel::check()?;
             // cycle loop stuff
             // if i == 0 {
             //     
@@ -169,11 +146,11 @@ impl Day<Info> for Day17 {
 
                 cycle_num += 1;
                 let d_r = rock_count - rocks_before;
-                let d_y = (y_max - y_before) as u64;
+                let d_y = y_max - y_before;
 
                 if cycle_num > CYCLES_BEFORE_STABLE && !did_cycle {
 
-                    if cycle.get(0).eq(&Some(&(d_y, d_r))) { // Cycle established!! Skip ahead to end
+                    if cycle.first().eq(&Some(&(d_y, d_r))) { // Cycle established!! Skip ahead to end
                         // dbg!(&cycle);
 
                         let (mut t_r, mut t_y) = (0, 0);
@@ -181,6 +158,10 @@ impl Day<Info> for Day17 {
                             t_y += d_y;
                             t_r += d_r;
                         }
+                        // A pass over the jets too short to drop a rock doesn't repeat anything
+                        if t_r == 0 {
+                            return Err(Error::solve("no cycle of dropped rocks found in the jet pattern"));
+                        }
                         let rocks_left = ROCK_LIMIT - rock_count;
                         let cycles_left = rocks_left / t_r;
                         y_increase = t_y * cycles_left;
@@ -236,10 +217,89 @@ impl Day<Info> for Day17 {
             }
         }
 
-        (y_max + y_increase) as i64
+        Ok((y_max + y_increase).into())
         // 0
         // solution(data, 1000000000000)
     }
+
+    // The tower after each of the first rocks comes to rest, like the puzzle text shows it
+    fn visualize(&self, data: &Info) -> Option<Result<Vec<Frame>>> {
+        const ROCKS_SHOWN: u64 = 10;
+
+        let mut frames = Vec::new();
+        let result = drop_rocks(data, ROCKS_SHOWN, |blocks_set, y_max| {
+            let caption = format!("Rock {} at rest, tower height {}", frames.len() + 1, y_max);
+            frames.push(Frame::new(caption, draw_tower(blocks_set, y_max)));
+        });
+
+        Some(result.map(|_| frames))
+    }
+}
+
+// Drops rock_limit rocks and returns the tower's height, calling on_rest after each one lands
+fn drop_rocks(data: &Info, rock_limit: u64, mut on_rest: impl FnMut(&HashSet<Vector2<u64>>, u64)) -> Result<u64> {
+    let mut blocks_set: HashSet<Vector2<u64>> = HashSet::new();
+    let mut y_max = 0;
+
+    let rocks = [IHor, X, L, IVert, O];
+    let mut rocks = rocks.iter().cycle();
+
+    let mut pos = Vector2 {x: 2, y: y_max + 4};
+    let mut rock = rocks.next().unwrap();
+    let mut rock_count = 0;
+    for jet in data.jet_pattern.iter().cycle() {
+        cancel::check()?;
+
+        match jet {
+            Left => {
+                if pos.x != 0 {
+                    pos.x -= 1;
+                    if intersects(pos, rock, &mut blocks_set) {
+                        pos.x += 1;
+                    }
+                }
+            }
+            Right => {
+                if pos.x != 6 {
+                    pos.x += 1;
+                    if intersects(pos, rock, &mut blocks_set) {
+                        pos.x -= 1;
+                    }
+                }
+            }
+        }
+
+        pos.y -= 1;
+        if intersects(pos, rock, &mut blocks_set) || pos.y == 0 {
+            pos.y += 1;
+            rock_count += 1;
+
+            y_max = insert_rock(pos, rock, y_max, &mut blocks_set);
+            on_rest(&blocks_set, y_max);
+
+            if rock_count == rock_limit {
+                break;
+            }
+
+            rock = rocks.next().unwrap();
+            pos = Vector2 {x: 2, y: y_max + 4};
+        }
+    }
+
+    Ok(y_max)
+}
+
+fn draw_tower(blocks_set: &HashSet<Vector2<u64>>, y_max: u64) -> String {
+    let mut result = String::new();
+    for y in (1..=y_max).rev() {
+        result.push('|');
+        for x in 0..7 {
+            result.push(if blocks_set.contains(&Vector2 {x, y}) { '#' } else { '.' });
+        }
+        result += "|\n";
+    }
+
+    result + "+-------+"
 }
 
 fn insert_rock(pos: Vector2<u64>, rock: &Rock, mut y_max: u64, blocks_set: &mut HashSet<Vector2<u64>>) -> u64 {
@@ -264,4 +324,10 @@ fn intersects(pos: Vector2<u64>, rock: &Rock, blocks_set: &mut HashSet<Vector2<u
     }
 
     false
+}
+
+// size jets on one line, each way as likely as the other
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let jets: String = (0..size.max(1)).map(|_| if rng.gen() { '>' } else { '<' }).collect();
+    jets + "\n"
 }
\ No newline at end of file
@@ -1,10 +1,19 @@
 ﻿use std::collections::HashSet;
 use std::ops::Add;
 use std::str::FromStr;
+use crate::answer::Answer;
+use crate::cancel;
 use crate::day::Day;
+use crate::error::{Error, Result};
+use crate::visualize::Frame;
+use rand::rngs::StdRng;
+use rand::Rng;
 
 pub struct Day18;
 
+// Same answers from a dense grid of the droplet's bounding box instead of hash sets
+pub struct Day18Grid;
+
 pub struct Info {
     points: Vec<Point>
 }
@@ -43,14 +52,15 @@ impl Add for Point {
 impl FromStr for Point {
     type Err = ();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let values: Vec<i8> = s
             .split(',')
             .map(|part| part.trim().parse::<i8>())
-            .collect::<Result<Vec<i8>, _>>()
+            .collect::<std::result::Result<Vec<i8>, _>>()
             .map_err(|_| ())?;
 
-        if values.len() != 3 {
+        // Neighbours and the padded bounds in part 2 are one step past each coordinate
+        if values.len() != 3 || values.iter().any(|x| *x == i8::MIN || *x == i8::MAX) {
             return Err(());
         }
 
@@ -58,17 +68,28 @@ impl FromStr for Point {
     }
 }
 
-impl Day<Info> for Day18 {
-    fn parse_file(&self, file_content: String) -> Info {
-        Info {
-            points: file_content.lines().map(|x| x.trim().parse().unwrap()).collect()
+impl Day for Day18 {
+    type Input = Info;
+
+    fn parse_file(&self, file_content: String) -> Result<Info> {
+        let mut points = Vec::new();
+        for (i, line) in file_content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let point = line.trim().parse().map_err(|_| {
+                Error::parse(i + 1, line, "expected three comma separated coordinates between -127 and 126")
+            })?;
+            points.push(point);
         }
+
+        Ok(Info { points })
     }
 
-    fn part_1(&self, data: &Info) -> i64 {
-        let point_set: HashSet<Point> = HashSet::from_iter(data.points.clone().into_iter());
+    fn part_1(&self, data: &Info) -> Result<Answer> {
+        let point_set: HashSet<Point> = HashSet::from_iter(data.points.clone());
         
-        let mut sum = 0;
+        let mut sum: u32 = 0;
         
         for point in point_set.iter() {
             for side in SIDES.iter() {
@@ -78,20 +99,20 @@ impl Day<Info> for Day18 {
             }
         }
         
-        sum
+        Ok(sum.into())
     }
 
-    fn part_2(&self, data: &Info) -> i64 {
-        let solid_set: HashSet<Point> = HashSet::from_iter(data.points.clone().into_iter());
+    fn part_2(&self, data: &Info) -> Result<Answer> {
+        let solid_set: HashSet<Point> = HashSet::from_iter(data.points.clone());
         
         #[inline]
         fn get_max_min(points: &Vec<Point>) -> (Point, Point) {
-            if points.len() == 0 {
+            if points.is_empty() {
                 return (Point(0, 0, 0), Point(0, 0, 0));
             }
             
-            let mut min = Point(std::i8::MAX, std::i8::MAX, std::i8::MAX);
-            let mut max = Point(std::i8::MIN, std::i8::MIN, std::i8::MIN);
+            let mut min = Point(i8::MAX, i8::MAX, i8::MAX);
+            let mut max = Point(i8::MIN, i8::MIN, i8::MIN);
             
             for point in points {
                 min = min.min_component(point);
@@ -102,14 +123,15 @@ impl Day<Info> for Day18 {
         }
         
         let mut visited = HashSet::new();
-        let mut visitable_sides = 0;
+        let mut visitable_sides: u32 = 0;
         let (min, max) = get_max_min(&data.points);
         let (min, max) = (min + Point(-1, -1, -1), max + Point(1, 1, 1));
         
         let mut visiting_stack = Vec::new();
-        visiting_stack.push(min.clone());
+        visiting_stack.push(min);
         
         while let Some(point) = visiting_stack.pop() {
+            cancel::check()?;
             if solid_set.contains(&point) {
                 visitable_sides += 1;
                 continue;
@@ -118,7 +140,7 @@ impl Day<Info> for Day18 {
                 continue;
             }
 
-            visited.insert(point.clone());
+            visited.insert(point);
 
             #[inline]
             fn can_move_to(point: &Point, visited: &HashSet<Point>, min: &Point, max: &Point) -> bool {
@@ -135,6 +157,159 @@ impl Day<Info> for Day18 {
             }
         }
 
-        visitable_sides
+        Ok(visitable_sides.into())
+    }
+
+    // One z slice of the droplet per frame, lava is #
+    fn visualize(&self, data: &Info) -> Option<Result<Vec<Frame>>> {
+        let solid_set: HashSet<Point> = HashSet::from_iter(data.points.clone());
+        let Some(first) = data.points.first() else {
+            return Some(Ok(Vec::new()));
+        };
+        let (min, max) = data.points.iter().fold((*first, *first), |(min, max), point| {
+            (min.min_component(point), max.max_component(point))
+        });
+
+        let mut frames = Vec::new();
+        for z in min.2..=max.2 {
+            let mut text = String::new();
+            for y in (min.1..=max.1).rev() {
+                for x in min.0..=max.0 {
+                    text.push(if solid_set.contains(&Point(x, y, z)) { '#' } else { '.' });
+                }
+                text.push('\n');
+            }
+            frames.push(Frame::new(format!("z = {}", z), text));
+        }
+
+        Some(Ok(frames))
+    }
+}
+
+// The droplet's bounding box padded by one cube on every side, so steam can get all the way round
+struct Grid {
+    min: Point,
+    size: (i32, i32, i32),
+    solid: Vec<bool>,
+}
+
+impl Grid {
+    fn new(points: &[Point]) -> Grid {
+        let (min, max) = points.iter().fold((points[0], points[0]), |(min, max), point| {
+            (min.min_component(point), max.max_component(point))
+        });
+        let (min, max) = (min + Point(-1, -1, -1), max + Point(1, 1, 1));
+        let size = (
+            (max.0 - min.0) as i32 + 1,
+            (max.1 - min.1) as i32 + 1,
+            (max.2 - min.2) as i32 + 1,
+        );
+
+        let mut grid = Grid { min, size, solid: vec![false; (size.0 * size.1 * size.2) as usize] };
+        for point in points {
+            let index = grid.index(point.0 as i32, point.1 as i32, point.2 as i32).unwrap();
+            grid.solid[index] = true;
+        }
+
+        grid
+    }
+
+    // None outside the box
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        let (x, y, z) = (x - self.min.0 as i32, y - self.min.1 as i32, z - self.min.2 as i32);
+        if x < 0 || y < 0 || z < 0 || x >= self.size.0 || y >= self.size.1 || z >= self.size.2 {
+            return None;
+        }
+
+        Some(((z * self.size.1 + y) * self.size.0 + x) as usize)
+    }
+
+    fn neighbours(&self, x: i32, y: i32, z: i32) -> impl Iterator<Item = (i32, i32, i32)> {
+        SIDES.iter().map(move |side| (x + side.0 as i32, y + side.1 as i32, z + side.2 as i32))
+    }
+
+    fn is_solid(&self, x: i32, y: i32, z: i32) -> bool {
+        self.index(x, y, z).is_some_and(|x| self.solid[x])
+    }
+}
+
+impl Day for Day18Grid {
+    type Input = Info;
+
+    fn parse_file(&self, file_content: String) -> Result<Info> {
+        Day18.parse_file(file_content)
+    }
+
+    fn part_1(&self, data: &Info) -> Result<Answer> {
+        if data.points.is_empty() {
+            return Ok(0.into());
+        }
+        let grid = Grid::new(&data.points);
+
+        let mut sum: u32 = 0;
+        // Cubes listed twice only count once
+        let mut counted = vec![false; grid.solid.len()];
+        for point in data.points.iter() {
+            let (x, y, z) = (point.0 as i32, point.1 as i32, point.2 as i32);
+            let index = grid.index(x, y, z).unwrap();
+            if counted[index] {
+                continue;
+            }
+            counted[index] = true;
+            sum += grid.neighbours(x, y, z).filter(|(x, y, z)| !grid.is_solid(*x, *y, *z)).count() as u32;
+        }
+
+        Ok(sum.into())
+    }
+
+    fn part_2(&self, data: &Info) -> Result<Answer> {
+        if data.points.is_empty() {
+            return Ok(0.into());
+        }
+        let grid = Grid::new(&data.points);
+
+        // Every face of lava the steam reaches from the box's corner
+        let mut faces: u32 = 0;
+        let mut visited = vec![false; grid.solid.len()];
+        let start = (grid.min.0 as i32, grid.min.1 as i32, grid.min.2 as i32);
+        visited[grid.index(start.0, start.1, start.2).unwrap()] = true;
+        let mut stack = vec![start];
+
+        while let Some((x, y, z)) = stack.pop() {
+            cancel::check()?;
+            for (x, y, z) in grid.neighbours(x, y, z) {
+                let Some(index) = grid.index(x, y, z) else {
+                    continue;
+                };
+                if grid.solid[index] {
+                    faces += 1;
+                } else if !visited[index] {
+                    visited[index] = true;
+                    stack.push((x, y, z));
+                }
+            }
+        }
+
+        Ok(faces.into())
     }
+}
+
+// size distinct cubes in a box about twice their volume, dense enough to leave air pockets inside
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    // An empty input isn't a droplet
+    let size = size.max(1);
+    // Far enough from the edges of an i8
+    let side = (size.saturating_mul(2) as f64).cbrt().ceil().clamp(1.0, 100.0) as i8;
+    let size = size.min((side as usize).pow(3));
+
+    let mut seen = HashSet::new();
+    let mut result = String::new();
+    while seen.len() < size {
+        let point = Point(rng.gen_range(0..side), rng.gen_range(0..side), rng.gen_range(0..side));
+        if seen.insert(point) {
+            result += &format!("{},{},{}\n", point.0, point.1, point.2);
+        }
+    }
+
+    result
 }
\ No newline at end of file
@@ -1,35 +1,45 @@
-﻿use crate::day::Day;
+﻿use crate::answer::Answer;
+use crate::cancel;
+use crate::day::Day;
+use crate::error::{Error, Result};
+use crate::visualize::Frame;
 use std::fmt::Display;
+use log::{debug, trace};
+use rand::rngs::StdRng;
+use rand::Rng;
 
 pub struct Day20;
 
+// Mixes a vector of indices into the original list instead of walking a linked list
+pub struct Day20Vec;
+
 pub struct Info {
     numbers: Vec<i32>,
 }
 
-impl Day<Info> for Day20 {
-    fn parse_file(&self, file_content: String) -> Info {
-        Info {
-            numbers: file_content.lines().map(|x| x.parse().unwrap()).collect(),
+impl Day for Day20 {
+    type Input = Info;
+
+    fn parse_file(&self, file_content: String) -> Result<Info> {
+        let mut numbers = Vec::new();
+        for (i, line) in file_content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let number = line.trim().parse().map_err(|_| Error::parse(i + 1, line, "expected a 32-bit integer"))?;
+            numbers.push(number);
         }
-    }
 
-    fn part_1(&self, data: &Info) -> i64 {
-        let mut list: Vec<Node<i64>> = Vec::with_capacity(data.numbers.len());
-        let len = data.numbers.len() as i64;
+        Ok(Info { numbers })
+    }
 
-        for (i, num) in data.numbers.iter().enumerate() {
-            let i = i as i64;
-            list.push(Node {
-                value: *num as i64,
-                left: (i - 1).rem_euclid(len) as usize,
-                right: (i + 1).rem_euclid(len) as usize,
-            });
-        }
+    fn part_1(&self, data: &Info) -> Result<Answer> {
+        let mut list = linked_list(data);
 
-        let zero_node = list.iter().position(|x| x.value == 0).unwrap();
+        let zero_node = find_zero(data)?;
 
         for i in 0..list.len() {
+            cancel::check()?;
             let num = list.get(i).unwrap().value;
             move_node(&mut list, i, num);
         }
@@ -38,14 +48,16 @@ impl Day<Info> for Day20 {
         let coord_2k = get_nth_right(&list, coord_1k, 1000);
         let coord_3k = get_nth_right(&list, coord_2k, 1000);
 
-        (list.get(coord_1k).unwrap().value
+        Ok((list.get(coord_1k).unwrap().value
             + list.get(coord_2k).unwrap().value
-            + list.get(coord_3k).unwrap().value) as i64
+            + list.get(coord_3k).unwrap().value).into())
     }
 
-    fn part_2(&self, data: &Info) -> i64 {
+    fn part_2(&self, data: &Info) -> Result<Answer> {
         let mut list: Vec<Node<i64>> = Vec::with_capacity(data.numbers.len());
         let len_64 = data.numbers.len() as i64;
+        // Moves wrap around the other len - 1 numbers, like in mix
+        let ring = (len_64 - 1).max(1);
 
         let mut true_list: Vec<i64> = Vec::with_capacity(data.numbers.len());
 
@@ -53,8 +65,8 @@ impl Day<Info> for Day20 {
         for (i, num) in data.numbers.iter().enumerate() {
             let true_num = (*num as i64) * DECRYPTION_KEY;
             true_list.push(true_num);
-            let simp_num = true_num % len_64;
-            println!("{} -> {} -> {}", num, true_num, simp_num);
+            let simp_num = true_num % ring;
+            trace!(target: "day20", "{} -> {} -> {}", num, true_num, simp_num);
             let i = i as i64;
             list.push(Node {
                 value: simp_num,
@@ -63,19 +75,19 @@ impl Day<Info> for Day20 {
             });
         }
 
-        let zero_node = list.iter().position(|x| x.value == 0).unwrap();
-        const NUM_MIXES: usize = 2;
-        print_true_linked(&true_list, &list, zero_node);
-        print_linked(&list, 0);
-        println!();
+        // Other numbers can be reduced to 0 too
+        let zero_node = find_zero(data)?;
+        const NUM_MIXES: usize = 10;
+        debug!(target: "day20", "Start: {}", format_true_linked(&true_list, &list, zero_node));
+        trace!(target: "day20", "Reduced: {}", format_linked(&list, 0));
 
         for m in 0..NUM_MIXES {
             for i in 0..list.len() {
+                cancel::check()?;
                 let num = list.get(i).unwrap().value;
                 move_node(&mut list, i, num)
             }
-            print!("{}: ", m + 1);
-            print_true_linked(&true_list, &list, zero_node);
+            debug!(target: "day20", "Mix {}: {}", m + 1, format_true_linked(&true_list, &list, zero_node));
         }
 
         let coord_1k = get_nth_right(&list, zero_node, 1000);
@@ -83,46 +95,90 @@ impl Day<Info> for Day20 {
         let coord_3k = get_nth_right(&list, coord_2k, 1000);
 
         unsafe {
-            true_list.get_unchecked(coord_1k)
+            Ok((true_list.get_unchecked(coord_1k)
                 + true_list.get_unchecked(coord_2k)
-                + true_list.get_unchecked(coord_3k)
+                + true_list.get_unchecked(coord_3k)).into())
         }
     }
+
+    // The ring read from 0 after each number of the first mix moves
+    fn visualize(&self, data: &Info) -> Option<Result<Vec<Frame>>> {
+        let mut list = linked_list(data);
+        let zero_node = match find_zero(data) {
+            Ok(zero_node) => zero_node,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let mut frames = vec![Frame::new("Initial arrangement", format_linked(&list, zero_node))];
+        for i in 0..list.len() {
+            let num = list.get(i).unwrap().value;
+            move_node(&mut list, i, num);
+            frames.push(Frame::new(format!("{} moves", num), format_linked(&list, zero_node)));
+        }
+
+        Some(Ok(frames))
+    }
+}
+
+// Numbers in file order, each linked to its neighbours in a ring
+fn linked_list(data: &Info) -> Vec<Node<i64>> {
+    let mut list: Vec<Node<i64>> = Vec::with_capacity(data.numbers.len());
+    let len = data.numbers.len() as i64;
+
+    for (i, num) in data.numbers.iter().enumerate() {
+        let i = i as i64;
+        list.push(Node {
+            value: *num as i64,
+            left: (i - 1).rem_euclid(len) as usize,
+            right: (i + 1).rem_euclid(len) as usize,
+        });
+    }
+
+    list
+}
+
+// The grove coordinates are counted from the number 0, so there has to be exactly one
+fn find_zero(data: &Info) -> Result<usize> {
+    let mut zeros = data.numbers.iter().enumerate().filter(|(_, x)| **x == 0).map(|(i, _)| i);
+    match (zeros.next(), zeros.next()) {
+        (Some(zero), None) => Ok(zero),
+        (None, _) => Err(Error::solve("the list doesn't contain a 0")),
+        (Some(_), Some(_)) => Err(Error::solve("the list contains more than one 0")),
+    }
 }
 
-fn print_linked<T: Display>(list: &Vec<Node<T>>, start_node: usize) {
-    print!("[");
+// Only called when logging or visualizing, the lists are as long as the input
+fn format_linked<T: Display>(list: &[Node<T>], start_node: usize) -> String {
     let s_node = list.get(start_node).unwrap();
-    print!("{}", s_node.value);
+    let mut result = format!("[{}", s_node.value);
     let mut cur_node = s_node.right;
     while cur_node != start_node {
         let node = list.get(cur_node).unwrap();
-        print!(", {}", node.value);
+        result += &format!(", {}", node.value);
         cur_node = node.right;
     }
 
-    println!("]");
+    result + "]"
 }
 
-fn print_true_linked<TRaw: Display, TTrue: Display>(
-    true_list: &Vec<TTrue>,
-    list: &Vec<Node<TRaw>>,
+fn format_true_linked<TRaw: Display, TTrue: Display>(
+    true_list: &[TTrue],
+    list: &[Node<TRaw>],
     start_node: usize,
-) {
-    print!("[");
+) -> String {
     let s_node = list.get(start_node).unwrap();
-    print!("{}", true_list.get(start_node).unwrap());
+    let mut result = format!("[{}", true_list.get(start_node).unwrap());
     let mut cur_node = s_node.right;
     while cur_node != start_node {
         let node = list.get(cur_node).unwrap();
-        print!(", {}", true_list.get(cur_node).unwrap());
+        result += &format!(", {}", true_list.get(cur_node).unwrap());
         cur_node = node.right;
     }
 
-    println!("]");
+    result + "]"
 }
 
-fn remove<T>(list: &mut Vec<Node<T>>, node_index: usize) {
+fn remove<T>(list: &mut [Node<T>], node_index: usize) {
     let node = list.get(node_index).unwrap();
     let left_index = node.left;
     let right_index = node.right;
@@ -132,7 +188,7 @@ fn remove<T>(list: &mut Vec<Node<T>>, node_index: usize) {
     right_node.left = left_index;
 }
 
-fn insert_right<T>(list: &mut Vec<Node<T>>, node_to_insert: usize, node_index: usize) {
+fn insert_right<T>(list: &mut [Node<T>], node_to_insert: usize, node_index: usize) {
     let left_node = list.get_mut(node_index).unwrap();
     let right_node_index = left_node.right;
     left_node.right = node_to_insert;
@@ -145,7 +201,7 @@ fn insert_right<T>(list: &mut Vec<Node<T>>, node_to_insert: usize, node_index: u
     right_node.left = node_to_insert;
 }
 
-fn insert_left<T>(list: &mut Vec<Node<T>>, node_to_insert: usize, node_index: usize) {
+fn insert_left<T>(list: &mut [Node<T>], node_to_insert: usize, node_index: usize) {
     let right_node = list.get_mut(node_index).unwrap();
     let left_node_index = right_node.left;
     right_node.left = node_to_insert;
@@ -158,7 +214,7 @@ fn insert_left<T>(list: &mut Vec<Node<T>>, node_to_insert: usize, node_index: us
     left_node.right = node_to_insert;
 }
 
-fn move_node<T>(list: &mut Vec<Node<T>>, node_index: usize, x: i64) {
+fn move_node<T>(list: &mut [Node<T>], node_index: usize, x: i64) {
     if x == 0 {
         return;
     }
@@ -179,16 +235,16 @@ fn move_node<T>(list: &mut Vec<Node<T>>, node_index: usize, x: i64) {
     }
 }
 
-fn get_nth_right<T>(list: &Vec<Node<T>>, mut node_index: usize, n: usize) -> usize {
-    for i in 0..n {
+fn get_nth_right<T>(list: &[Node<T>], mut node_index: usize, n: usize) -> usize {
+    for _ in 0..n {
         node_index = list.get(node_index).unwrap().right;
     }
 
     node_index
 }
 
-fn get_nth_left<T>(list: &Vec<Node<T>>, mut node_index: usize, n: usize) -> usize {
-    for i in 0..n {
+fn get_nth_left<T>(list: &[Node<T>], mut node_index: usize, n: usize) -> usize {
+    for _ in 0..n {
         node_index = list.get(node_index).unwrap().left;
     }
 
@@ -206,3 +262,55 @@ impl<T: Display> Display for Node<T> {
         write!(f, "{} - [{}, {}]", self.value, self.left, self.right)
     }
 }
+
+impl Day for Day20Vec {
+    type Input = Info;
+
+    fn parse_file(&self, file_content: String) -> Result<Info> {
+        Day20.parse_file(file_content)
+    }
+
+    fn part_1(&self, data: &Info) -> Result<Answer> {
+        let numbers: Vec<i64> = data.numbers.iter().map(|x| *x as i64).collect();
+        Ok(mix(&numbers, find_zero(data)?, 1)?.into())
+    }
+
+    fn part_2(&self, data: &Info) -> Result<Answer> {
+        const DECRYPTION_KEY: i64 = 811589153;
+        let numbers: Vec<i64> = data.numbers.iter().map(|x| *x as i64 * DECRYPTION_KEY).collect();
+        Ok(mix(&numbers, find_zero(data)?, 10)?.into())
+    }
+}
+
+// Sum of the grove coordinates after mixing. order holds the original indices in their current order.
+fn mix(numbers: &[i64], zero: usize, rounds: usize) -> Result<i64> {
+    let mut order: Vec<usize> = (0..numbers.len()).collect();
+
+    // With one number there's nowhere to move to
+    if numbers.len() > 1 {
+        // The moving number is out of the ring while it moves, so it's back where it started after len - 1 steps
+        let ring = numbers.len() as i64 - 1;
+        for _ in 0..rounds {
+            for (i, number) in numbers.iter().enumerate() {
+                cancel::check()?;
+                let position = order.iter().position(|x| *x == i).unwrap();
+                order.remove(position);
+                order.insert((position as i64 + number).rem_euclid(ring) as usize, i);
+            }
+        }
+    }
+
+    let zero_position = order.iter().position(|x| *x == zero).unwrap();
+    Ok([1000, 2000, 3000].iter().map(|x| numbers[order[(zero_position + x) % order.len()]]).sum())
+}
+
+// size numbers with a single 0, from a range narrow enough that some come up twice
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let limit = size.saturating_mul(2).min(10000) as i32;
+
+    let mut numbers: Vec<i32> = (1..size).map(|_| rng.gen_range(1..=limit) * if rng.gen() { 1 } else { -1 }).collect();
+    numbers.insert(rng.gen_range(0..size), 0);
+
+    numbers.iter().map(|x| format!("{}\n", x)).collect()
+}
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+use std::time::Duration;
+use std::fmt::{Display, Formatter};
+use crate::day::Part;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum InputProblem {
+    Missing,
+    Empty,
+}
+
+// Clone so errors from worker threads can be replayed in order
+#[derive(Clone, Debug)]
+pub enum Error {
+    Io {
+        path: String,
+        source: Arc<std::io::Error>,
+    },
+    // A puzzle input the run needs is missing or blank
+    Input {
+        day: Option<u8>,
+        path: String,
+        problem: InputProblem,
+    },
+    // Bad puzzle input, line is 1-based
+    Parse {
+        day: Option<u8>,
+        line: usize,
+        text: String,
+        message: String,
+    },
+    // Input parsed fine but a part can't be solved for it
+    Solve {
+        day: Option<u8>,
+        part: Option<Part>,
+        message: String,
+    },
+    // A part gave up, limit is its time budget when that ran out
+    Cancelled {
+        day: Option<u8>,
+        part: Option<Part>,
+        limit: Option<Duration>,
+    },
+    UnknownDay {
+        day: u8,
+        available: Vec<u8>,
+    },
+    // A JSON file such as the answers manifest couldn't be read back
+    Json {
+        path: String,
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn io(path: &str, source: std::io::Error) -> Error {
+        Error::Io {
+            path: path.to_string(),
+            source: Arc::new(source),
+        }
+    }
+
+    pub fn input(path: &str, problem: InputProblem) -> Error {
+        Error::Input {
+            day: None,
+            path: path.to_string(),
+            problem,
+        }
+    }
+
+    pub fn cancelled(limit: Option<Duration>) -> Error {
+        Error::Cancelled {
+            day: None,
+            part: None,
+            limit,
+        }
+    }
+
+    pub fn parse(line: usize, text: &str, message: impl Into<String>) -> Error {
+        Error::Parse {
+            day: None,
+            line,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn json(path: &str, error: serde_json::Error) -> Error {
+        Error::Json {
+            path: path.to_string(),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn solve(message: impl Into<String>) -> Error {
+        Error::Solve {
+            day: None,
+            part: None,
+            message: message.into(),
+        }
+    }
+
+    // Days don't know their own number, so the registry fills it in
+    pub fn with_day(mut self, number: u8) -> Error {
+        match &mut self {
+            Error::Input { day, .. }
+            | Error::Parse { day, .. }
+            | Error::Solve { day, .. }
+            | Error::Cancelled { day, .. } => *day = Some(number),
+            Error::Io { .. } | Error::UnknownDay { .. } | Error::Json { .. } => {}
+        }
+        self
+    }
+
+    pub fn with_part(mut self, number: Part) -> Error {
+        if let Error::Solve { part, .. } | Error::Cancelled { part, .. } = &mut self {
+            *part = Some(number);
+        }
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "Could not read \"{}\": {}", path, source),
+            Error::Input { day, path, problem } => {
+                match day {
+                    Some(day) => write!(f, "Day {} needs \"{}\"", day, path)?,
+                    None => write!(f, "Needs \"{}\"", path)?,
+                }
+                match problem {
+                    InputProblem::Missing => write!(f, ", but the file doesn't exist"),
+                    InputProblem::Empty => write!(f, ", but it's empty"),
+                }
+            }
+            Error::Parse { day, line, text, message } => {
+                if let Some(day) = day {
+                    write!(f, "Day {} - ", day)?;
+                }
+                write!(f, "Parse error on line {}: {}\n    {:?}", line, message, text)
+            }
+            Error::Solve { day, part, message } => {
+                if let Some(day) = day {
+                    write!(f, "Day {} - ", day)?;
+                }
+                match part {
+                    Some(Part::One) => write!(f, "Part 1 failed: {}", message),
+                    Some(Part::Two) => write!(f, "Part 2 failed: {}", message),
+                    None => write!(f, "Failed: {}", message),
+                }
+            }
+            Error::Cancelled { day, part, limit } => {
+                if let Some(day) = day {
+                    write!(f, "Day {} - ", day)?;
+                }
+                match part {
+                    Some(part) => write!(f, "Part {}", part.number())?,
+                    None => write!(f, "Run")?,
+                }
+                match limit {
+                    Some(limit) => write!(f, " timed out after {:.2?}", limit),
+                    None => write!(f, " was cancelled"),
+                }
+            }
+            Error::UnknownDay { day, available } => {
+                let available: Vec<String> = available.iter().map(|x| x.to_string()).collect();
+                write!(f, "Day {} is not implemented (available: {})", day, available.join(", "))
+            }
+            Error::Json { path, message } => write!(f, "Invalid JSON in \"{}\": {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use regex::Regex;
+use crate::answer::Answer;
+use crate::day::{Part, Solution};
+use crate::error::{Error, Result};
+use crate::generate::Generator;
+use crate::registry::DayEntry;
+
+// Shrunk inputs go here, copying one to input/dayNNe<name>.txt makes it an example
+pub const FUZZ_DIR: &str = "target/fuzz";
+
+#[derive(Copy, Clone, Debug)]
+pub struct FuzzOptions {
+    pub cases: usize,
+    // Largest input to generate, each case picks a size up to it
+    pub size: usize,
+    // Case n is generated from seed + n, so a single case can be rerun with --seed and --cases 1
+    pub seed: u64,
+    pub part: Option<Part>,
+}
+
+// An input the reference and another implementation answer differently, shrunk as far as it goes
+pub struct Disagreement {
+    pub case: usize,
+    pub part: Part,
+    pub reference: &'static str,
+    pub implementation: &'static str,
+    pub generated_lines: usize,
+    pub input: String,
+    pub expected: Result<Answer>,
+    pub answer: Result<Answer>,
+}
+
+impl Disagreement {
+    pub fn path(&self, day: u8) -> String {
+        format!("{}/day{:02}-{}-part{}.txt", FUZZ_DIR, day, self.implementation, self.part.number())
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    }
+}
+
+// A panic counts as an error, the panic hook is silenced while fuzzing
+fn outcome(solver: &dyn Solution, input: &str, part: Part) -> Result<Answer> {
+    panic::catch_unwind(AssertUnwindSafe(|| solver.solve(input, part)))
+        .unwrap_or_else(|e| Err(Error::solve(format!("panicked: {}", panic_message(e.as_ref())))))
+}
+
+// How the reference and another implementation differ on an input
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Difference {
+    Answers,
+    ReferenceFailed,
+    ImplementationFailed,
+}
+
+fn difference(expected: &Result<Answer>, answer: &Result<Answer>) -> Option<Difference> {
+    match (expected, answer) {
+        (Ok(expected), Ok(answer)) if expected == answer => None,
+        (Ok(_), Ok(_)) => Some(Difference::Answers),
+        // An input both reject says nothing about either of them
+        (Err(_), Err(_)) => None,
+        (Err(_), Ok(_)) => Some(Difference::ReferenceFailed),
+        (Ok(_), Err(_)) => Some(Difference::ImplementationFailed),
+    }
+}
+
+fn join(lines: &[String]) -> String {
+    lines.iter().map(|x| format!("{}\n", x)).collect()
+}
+
+// Drops ever smaller runs of lines, returns whether any went
+fn drop_lines(lines: &mut Vec<String>, disagrees: &impl Fn(&str) -> bool) -> bool {
+    let mut any_removed = false;
+    let mut chunk = (lines.len() / 2).max(1);
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<String> = lines[..start].iter().chain(lines[end..].iter()).cloned().collect();
+            if disagrees(&join(&candidate)) {
+                *lines = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+        any_removed |= removed;
+
+        match chunk {
+            1 if !removed => return any_removed,
+            1 => {}
+            _ => chunk /= 2,
+        }
+    }
+}
+
+// Drops the line giving something a name, like a valve, together with every mention of it on the
+// others. Dropping just the line would leave them pointing at nothing, which both sides reject.
+fn drop_names(lines: &mut Vec<String>, disagrees: &impl Fn(&str) -> bool) -> bool {
+    let name = Regex::new(r"\b[A-Z]{2,}\b").unwrap();
+    let mut any_removed = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(found) = name.find(&lines[i]) else {
+            i += 1;
+            continue;
+        };
+        // Out of a list along with its separator
+        let mention = Regex::new(&format!(r", {0}\b|\b{0}, |\b{0}\b", found.as_str())).unwrap();
+        let candidate: Vec<String> = lines
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, x)| mention.replace_all(x, "").into_owned())
+            .collect();
+        if disagrees(&join(&candidate)) {
+            *lines = candidate;
+            any_removed = true;
+        } else {
+            i += 1;
+        }
+    }
+
+    any_removed
+}
+
+// Tries each change a pattern match allows in turn, rescanning a line after every accepted change
+fn replace_matches(
+    lines: &mut [String],
+    pattern: &Regex,
+    replacements: impl Fn(&str) -> Vec<String>,
+    disagrees: &impl Fn(&str) -> bool,
+) -> bool {
+    let mut any_replaced = false;
+    for i in 0..lines.len() {
+        'line: loop {
+            let line = lines[i].clone();
+            for found in pattern.find_iter(&line) {
+                for replacement in replacements(found.as_str()) {
+                    let mut candidate = lines.to_vec();
+                    candidate[i] = format!("{}{}{}", &line[..found.start()], replacement, &line[found.end()..]);
+                    if disagrees(&join(&candidate)) {
+                        lines[i] = candidate.swap_remove(i);
+                        any_replaced = true;
+                        continue 'line;
+                    }
+                }
+            }
+            break;
+        }
+    }
+
+    any_replaced
+}
+
+// Greedy delta debugging: drops lines and named things and pulls numbers towards 0 for as long as
+// that still disagrees. It's up to disagrees to turn down inputs that stray from what's being shrunk.
+fn shrink(input: &str, disagrees: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<String> = input.lines().map(str::to_string).collect();
+
+    let number = Regex::new(r"-?\d+").unwrap();
+    let smaller = |text: &str| match text.parse::<i64>() {
+        Ok(value) => [0, value / 2, value - value.signum()]
+            .into_iter()
+            .filter(|x| *x != value)
+            .map(|x| x.to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    loop {
+        let dropped_lines = drop_lines(&mut lines, &disagrees);
+        let dropped_names = drop_names(&mut lines, &disagrees);
+        let smaller_numbers = replace_matches(&mut lines, &number, smaller, &disagrees);
+        if !dropped_lines && !dropped_names && !smaller_numbers {
+            break;
+        }
+    }
+
+    join(&lines)
+}
+
+// Checks every implementation against the first one on generated inputs, stopping at the first disagreement
+pub fn fuzz(entry: &DayEntry, generator: Generator, options: &FuzzOptions) -> Option<Disagreement> {
+    let (reference, others) = entry.implementations.split_first()?;
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut result = None;
+    'cases: for case in 0..options.cases {
+        let mut rng = StdRng::seed_from_u64(options.seed.wrapping_add(case as u64));
+        let size = rng.gen_range(1..=options.size.max(1));
+        let input = generator(&mut rng, size);
+
+        for part in Part::ALL.into_iter().filter(|x| x.is_selected(options.part)) {
+            let expected = outcome(reference.solver, &input, part);
+            for other in others {
+                let Some(kind) = difference(&expected, &outcome(other.solver, &input, part)) else {
+                    continue;
+                };
+
+                // Only inputs that differ the same way, so a wrong answer can't shrink into an input
+                // one of them merely rejects
+                let generated_lines = input.lines().count();
+                let input = shrink(&input, |input| {
+                    difference(&outcome(reference.solver, input, part), &outcome(other.solver, input, part)) == Some(kind)
+                });
+                result = Some(Disagreement {
+                    case,
+                    part,
+                    reference: reference.name,
+                    implementation: other.name,
+                    generated_lines,
+                    expected: outcome(reference.solver, &input, part),
+                    answer: outcome(other.solver, &input, part),
+                    input,
+                });
+                break 'cases;
+            }
+        }
+    }
+    panic::set_hook(hook);
+
+    result
+}
+
+// Writes the shrunk input to the fuzz directory, returning where it went
+pub fn save(day: u8, disagreement: &Disagreement) -> Result<String> {
+    let path = disagreement.path(day);
+    std::fs::create_dir_all(FUZZ_DIR).map_err(|e| Error::io(FUZZ_DIR, e))?;
+    std::fs::write(&path, &disagreement.input).map_err(|e| Error::io(&path, e))?;
+
+    Ok(path)
+}
+
+pub fn print_disagreement(disagreement: &Disagreement, path: &str, seed: u64) {
+    let describe = |x: &Result<Answer>| match x {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("Error: {}", e),
+    };
+
+    println!(
+        "Case {} (seed {}): {} and {} disagree on Part {}\x07",
+        disagreement.case,
+        seed.wrapping_add(disagreement.case as u64),
+        disagreement.reference,
+        disagreement.implementation,
+        disagreement.part.number()
+    );
+    println!(
+        "Shrunk from {} to {} lines, written to {}",
+        disagreement.generated_lines,
+        disagreement.input.lines().count(),
+        path
+    );
+    println!("    {:<14} {}", disagreement.reference, describe(&disagreement.expected));
+    println!("    {:<14} {}", disagreement.implementation, describe(&disagreement.answer));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day16;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn drop_lines_keeps_what_disagrees_needs() {
+        let mut kept = lines("a\nb\nc\nd\ne\nf");
+        let disagrees = |input: &str| input.contains("b\n") && input.contains("d\n");
+
+        assert!(drop_lines(&mut kept, &disagrees));
+        assert_eq!(kept, lines("b\nd"));
+        assert!(!drop_lines(&mut kept, &disagrees));
+    }
+
+    #[test]
+    fn replace_matches_rescans_after_a_change() {
+        let mut kept = lines("x=aaaa y=b");
+        let pattern = Regex::new("a+").unwrap();
+        let shorter = |text: &str| vec![text[1..].to_string()];
+        let disagrees = |input: &str| input.contains("x=a");
+
+        assert!(replace_matches(&mut kept, &pattern, shorter, &disagrees));
+        assert_eq!(kept, lines("x=a y=b"));
+    }
+
+    #[test]
+    fn shrink_pulls_numbers_towards_zero() {
+        let disagrees = |input: &str| input.lines().any(|x| x.parse::<i64>().is_ok_and(|x| x >= 7 || x <= -3));
+
+        assert_eq!(shrink("3\n100\n5\n", disagrees), "7\n");
+        assert_eq!(shrink("3\n-100\n5\n", disagrees), "-3\n");
+    }
+
+    #[test]
+    fn shrink_keeps_lines_and_numbers_it_cant_lose() {
+        // Needs a 0 and at least two other lines, adding up to at least 10
+        let disagrees = |input: &str| {
+            let numbers: Vec<i64> = input.lines().map(|x| x.parse().unwrap()).collect();
+            numbers.contains(&0) && numbers.iter().filter(|x| **x != 0).sum::<i64>() >= 10 && numbers.len() >= 3
+        };
+
+        let shrunk = shrink("4\n0\n9\n12\n1\n", disagrees);
+        let numbers: Vec<i64> = shrunk.lines().map(|x| x.parse().unwrap()).collect();
+        assert_eq!(numbers.len(), 3);
+        assert!(numbers.contains(&0));
+        assert_eq!(numbers.iter().sum::<i64>(), 10);
+    }
+
+    #[test]
+    fn shrink_drops_valves_with_their_tunnels() {
+        // input/day16e2.txt with dead ends and a longer way round, none of which changes the answer
+        let input = "Valve AA has flow rate=0; tunnels lead to valves PG, KD, QQ\n\
+                     Valve QQ has flow rate=0; tunnels lead to valves AA, ZZ, YY\n\
+                     Valve PG has flow rate=1; tunnels lead to valves AA, WW\n\
+                     Valve WW has flow rate=0; tunnel leads to valve PG\n\
+                     Valve KD has flow rate=0; tunnels lead to valves AA, RT\n\
+                     Valve ZZ has flow rate=0; tunnels lead to valves QQ, RT\n\
+                     Valve YY has flow rate=0; tunnel leads to valve QQ\n\
+                     Valve RT has flow rate=0; tunnels lead to valves ZZ, KD, JY\n\
+                     Valve JY has flow rate=1; tunnels lead to valves RT, AC\n\
+                     Valve AC has flow rate=1; tunnel leads to valve JY\n";
+        let disagrees = |input: &str| day16::Day16Bitmask.solve(input, Part::Two).is_ok_and(|x| x == 66.into());
+        assert!(disagrees(input));
+
+        assert_eq!(
+            shrink(input, disagrees),
+            "Valve AA has flow rate=0; tunnels lead to valves PG, KD\n\
+             Valve PG has flow rate=1; tunnels lead to valves AA\n\
+             Valve KD has flow rate=0; tunnels lead to valves AA, RT\n\
+             Valve RT has flow rate=0; tunnels lead to valves KD, JY\n\
+             Valve JY has flow rate=1; tunnels lead to valves RT, AC\n\
+             Valve AC has flow rate=1; tunnel leads to valve JY\n"
+        );
+    }
+
+    #[test]
+    fn differences_by_kind() {
+        let (three, four): (Result<Answer>, Result<Answer>) = (Ok(3.into()), Ok(4.into()));
+        let failed: Result<Answer> = Err(Error::solve("failed"));
+
+        assert_eq!(difference(&three, &three), None);
+        assert_eq!(difference(&three, &four), Some(Difference::Answers));
+        assert_eq!(difference(&failed, &four), Some(Difference::ReferenceFailed));
+        assert_eq!(difference(&three, &failed), Some(Difference::ImplementationFailed));
+        assert_eq!(difference(&failed, &failed), None);
+    }
+}
@@ -0,0 +1,16 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Makes a random valid puzzle input, size is in the day's own unit (valves, jets, cubes, numbers)
+pub type Generator = fn(&mut StdRng, usize) -> String;
+
+// Any seed will do, callers print it so the input can be made again
+pub fn random_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_nanos() as u64)
+}
+
+// The same seed and size always give the same input
+pub fn generate(generator: Generator, size: usize, seed: u64) -> String {
+    generator(&mut StdRng::seed_from_u64(seed), size)
+}
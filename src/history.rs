@@ -0,0 +1,288 @@
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::answer::Answer;
+use crate::day::{InputKind, Part, PartResult};
+use crate::error::{Error, Result};
+use crate::memory;
+use crate::store;
+
+// One JSON record per line, so runs only ever append
+// Kept out of target/ so cargo clean doesn't wipe it
+pub const HISTORY_FILE: &str = ".aoc/history.jsonl";
+
+// A full input part from one run
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    // Seconds since the Unix epoch
+    pub timestamp: u64,
+    // Short hash of HEAD, with +dirty for uncommitted changes. None outside a git checkout.
+    pub commit: Option<String>,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    #[serde(with = "crate::bench::nanos")]
+    pub elapsed: Duration,
+}
+
+// Days sharing the CPU or counting every allocation run slower, their timings would only show up
+// as slowdowns next to the others
+pub fn comparable_timings(jobs: usize) -> bool {
+    jobs <= 1 && !memory::enabled()
+}
+
+pub fn current_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git").args(args).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let hash = git(&["rev-parse", "--short", "HEAD"])?;
+    // Only tracked files count, new scratch files don't make the build different
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|x| !x.is_empty());
+    Some(match dirty {
+        true => hash + "+dirty",
+        false => hash,
+    })
+}
+
+pub fn entries(day: u8, results: &[PartResult], commit: Option<&str>) -> Vec<Entry> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs());
+    results
+        .iter()
+        .filter(|x| x.input == InputKind::Full)
+        // Parts that ran out of time have no timing worth keeping
+        .filter_map(|x| {
+            Some(Entry {
+                timestamp,
+                commit: commit.map(str::to_string),
+                day,
+                part: x.part.number(),
+                answer: x.answer.clone().ok()?,
+                elapsed: x.elapsed,
+            })
+        })
+        .collect()
+}
+
+pub fn append(path: &str, entries: &[Entry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    store::create_parent(path)?;
+
+    let mut content = String::new();
+    for entry in entries {
+        content += &serde_json::to_string(entry).map_err(|e| Error::json(path, e))?;
+        content.push('\n');
+    }
+
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path).map_err(|e| Error::io(path, e))?;
+    file.write_all(content.as_bytes()).map_err(|e| Error::io(path, e))
+}
+
+// No history yet is just empty
+pub fn load(path: &str) -> Result<Vec<Entry>> {
+    let Some(content) = store::read(path)? else {
+        return Ok(Vec::new());
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| Error::Json {
+                path: path.to_string(),
+                message: format!("record on line {}: {}", i + 1, e),
+            })
+        })
+        .collect()
+}
+
+// A part that got slower between two consecutive commits
+pub struct Slowdown {
+    pub commit: String,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+pub struct Trend {
+    pub day: u8,
+    pub part: Part,
+    pub runs: Vec<Entry>,
+    pub slowdowns: Vec<Slowdown>,
+}
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+// Groups the history by day and part. Runs are compared commit by commit using the median
+// of each commit's runs, and a part counts as slower once that grows by more than threshold times.
+pub fn trends(entries: &[Entry], threshold: f64) -> Vec<Trend> {
+    let mut keys: Vec<(u8, u8)> = entries.iter().map(|x| (x.day, x.part)).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|(day, part)| {
+            let runs: Vec<Entry> = entries.iter().filter(|x| x.day == day && x.part == part).cloned().collect();
+
+            // Commits in the order they were first run
+            let mut commits: Vec<String> = Vec::new();
+            for run in runs.iter() {
+                let commit = run.commit.clone().unwrap_or_else(|| "unknown".to_string());
+                if !commits.contains(&commit) {
+                    commits.push(commit);
+                }
+            }
+            let medians: Vec<(String, Duration)> = commits
+                .into_iter()
+                .map(|commit| {
+                    let times = runs
+                        .iter()
+                        .filter(|x| x.commit.as_deref().unwrap_or("unknown") == commit)
+                        .map(|x| x.elapsed)
+                        .collect();
+                    (commit, median(times))
+                })
+                .collect();
+
+            let slowdowns = medians
+                .windows(2)
+                .filter(|x| x[1].1.as_secs_f64() > x[0].1.as_secs_f64() * threshold)
+                .map(|x| Slowdown {
+                    commit: x[1].0.clone(),
+                    before: x[0].1,
+                    after: x[1].1,
+                })
+                .collect();
+
+            Some(Trend {
+                day,
+                part: Part::from_number(part)?,
+                runs,
+                slowdowns,
+            })
+        })
+        .collect()
+}
+
+fn age(timestamp: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs());
+    match now.saturating_sub(timestamp) {
+        x if x < 60 => format!("{}s ago", x),
+        x if x < 60 * 60 => format!("{}m ago", x / 60),
+        x if x < 60 * 60 * 24 => format!("{}h ago", x / (60 * 60)),
+        x => format!("{}d ago", x / (60 * 60 * 24)),
+    }
+}
+
+// Shows the latest runs of each part, newest last, then any commit that made it slower
+pub fn print_trends(trends: &[Trend], limit: usize) {
+    for trend in trends {
+        let best = trend.runs.iter().map(|x| x.elapsed).min().unwrap_or_default();
+        println!(
+            "<--------    Day {} Part {}: {} runs, best {:.2?}    -------->",
+            trend.day,
+            trend.part.number(),
+            trend.runs.len(),
+            best
+        );
+
+        for run in trend.runs.iter().skip(trend.runs.len().saturating_sub(limit)) {
+            let ratio = run.elapsed.as_secs_f64() / best.as_secs_f64();
+            println!(
+                "{:>8}  {:<14} {:>11}  {:>5.2}x best   {}",
+                age(run.timestamp),
+                run.commit.as_deref().unwrap_or("unknown"),
+                format!("{:.2?}", run.elapsed),
+                ratio,
+                run.answer.one_line()
+            );
+        }
+
+        for slowdown in trend.slowdowns.iter() {
+            println!(
+                "Slower at {}: {:.2?} -> {:.2?}, {:.2}x",
+                slowdown.commit,
+                slowdown.before,
+                slowdown.after,
+                slowdown.after.as_secs_f64() / slowdown.before.as_secs_f64()
+            );
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(commit: &str, day: u8, part: u8, millis: u64) -> Entry {
+        Entry {
+            timestamp: 0,
+            commit: Some(commit.to_string()),
+            day,
+            part,
+            answer: 1.into(),
+            elapsed: Duration::from_millis(millis),
+        }
+    }
+
+    fn slowdowns(trend: &Trend) -> Vec<(&str, u64, u64)> {
+        trend
+            .slowdowns
+            .iter()
+            .map(|x| (x.commit.as_str(), x.before.as_millis() as u64, x.after.as_millis() as u64))
+            .collect()
+    }
+
+    #[test]
+    fn groups_by_day_and_part() {
+        let entries = [entry("a", 20, 1, 5), entry("a", 16, 2, 5), entry("a", 16, 1, 5), entry("b", 16, 1, 5)];
+        let trends = trends(&entries, 1.2);
+
+        let keys: Vec<(u8, u8, usize)> = trends.iter().map(|x| (x.day, x.part.number(), x.runs.len())).collect();
+        assert_eq!(keys, vec![(16, 1, 2), (16, 2, 1), (20, 1, 1)]);
+    }
+
+    #[test]
+    fn flags_the_commit_that_got_slower() {
+        let entries = [
+            entry("a", 16, 1, 10),
+            entry("b", 16, 1, 11),
+            entry("c", 16, 1, 20),
+            // Getting faster again isn't flagged
+            entry("d", 16, 1, 10),
+        ];
+
+        assert_eq!(slowdowns(&trends(&entries, 1.2)[0]), vec![("c", 11, 20)]);
+    }
+
+    #[test]
+    fn compares_medians_so_one_slow_run_doesnt_count() {
+        let entries = [
+            entry("a", 16, 1, 10),
+            entry("a", 16, 1, 10),
+            entry("b", 16, 1, 50),
+            entry("b", 16, 1, 10),
+            entry("b", 16, 1, 11),
+            // Commits keep the order they were first run in
+            entry("a", 16, 1, 10),
+        ];
+
+        assert!(slowdowns(&trends(&entries, 1.2)[0]).is_empty());
+    }
+
+    #[test]
+    fn runs_outside_git_count_as_one_commit() {
+        let mut entries = [entry("a", 16, 1, 10), entry("", 16, 1, 30), entry("", 16, 1, 30)];
+        entries[1].commit = None;
+        entries[2].commit = None;
+
+        assert_eq!(slowdowns(&trends(&entries, 1.2)[0]), vec![("unknown", 10, 30)]);
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::Read;
+use crate::error::{Error, InputProblem, Result};
+
+pub const INPUT_DIR: &str = "input";
+
+// input/day16.txt
+pub fn full_input_path(day: u8) -> String {
+    format!("{}/day{:02}.txt", INPUT_DIR, day)
+}
+
+// input/day16e2.txt for the example named day16e2
+pub fn example_input_path(name: &str) -> String {
+    format!("{}/{}.txt", INPUT_DIR, name)
+}
+
+// Every input/day16e*.txt as (name, path), named after the file like day16e2
+pub fn discover_examples(day: u8) -> Vec<(String, String)> {
+    let prefix = format!("day{:02}e", day);
+    let Ok(entries) = std::fs::read_dir(INPUT_DIR) else {
+        return Vec::new();
+    };
+
+    let mut examples: Vec<(String, String)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file_name| {
+            let name = file_name.strip_suffix(".txt")?;
+            name.starts_with(&prefix).then(|| (name.to_string(), format!("{}/{}", INPUT_DIR, file_name)))
+        })
+        .collect();
+    examples.sort();
+
+    examples
+}
+
+// Where a puzzle input comes from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    File(String),
+    Stdin,
+    // Input that's already in memory, e.g. from a test or a generator
+    Text(String),
+}
+
+impl InputSource {
+    // "-" reads stdin, like most command line tools
+    pub fn from_arg(arg: &str) -> InputSource {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(path.to_string()),
+        }
+    }
+
+    // Missing and blank inputs are errors naming the file, so they don't get solved as nonsense
+    pub fn read(&self) -> Result<String> {
+        let content = match self {
+            InputSource::File(path) => match std::fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(Error::input(&self.to_string(), InputProblem::Missing));
+                }
+                Err(e) => return Err(Error::io(path, e)),
+            },
+            InputSource::Stdin => {
+                let mut content = String::new();
+                std::io::stdin().read_to_string(&mut content).map_err(|e| Error::io("<stdin>", e))?;
+                content
+            }
+            InputSource::Text(content) => content.clone(),
+        };
+
+        // Some editors save an empty file as just a byte order mark
+        if content.trim_start_matches('\u{feff}').trim().is_empty() {
+            return Err(Error::input(&self.to_string(), InputProblem::Empty));
+        }
+
+        Ok(content)
+    }
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path),
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::Text(_) => write!(f, "<text>"),
+        }
+    }
+}
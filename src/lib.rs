@@ -0,0 +1,23 @@
+pub mod answer;
+pub mod bench;
+pub mod cancel;
+pub mod compare;
+pub mod day;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day20;
+pub mod error;
+pub mod fuzz;
+pub mod generate;
+pub mod history;
+pub mod input;
+pub mod manifest;
+pub mod memory;
+pub mod output;
+pub mod parallel;
+pub mod registry;
+pub mod report;
+pub mod store;
+pub mod visualize;
+pub mod watch;
@@ -1,52 +1,393 @@
-mod day;
-mod day16;
-mod day17;
-mod day18;
-mod day20;
-
-use crate::day::{run_with_test, run_with_test_2};
-use crate::day16::Day16;
-use crate::day17::Day17;
-use crate::day18::Day18;
-use crate::day20::Day20;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-
-fn main() -> std::io::Result<()> {
-    // test_day16()?;
-    // test_day17()?;
-    test_day20()?;
-
-    // let max = 3;
-    // let mut arr = (-max..=max).collect::<Vec<i32>>();
-    // let mut rng = thread_rng();
-    // arr.shuffle(&mut rng);
-    // dbg!(arr);
+mod cli;
 
-    Ok(())
+use std::process::ExitCode;
+use std::time::Duration;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use aoc2022::bench::{self, Baseline};
+use aoc2022::compare;
+use aoc2022::day::{Inputs, Part, RunOptions};
+use aoc2022::error;
+use aoc2022::fuzz::{self, FuzzOptions};
+use aoc2022::generate;
+use aoc2022::history::{self, HISTORY_FILE};
+use aoc2022::input::{self, InputSource};
+use aoc2022::manifest::{Manifest, MANIFEST_FILE};
+use aoc2022::output::{Reporter, TextReporter};
+use aoc2022::parallel::{self, DayRun, ParallelOptions};
+use aoc2022::registry::{self, DayEntry};
+use aoc2022::report::{self, ReportCollector};
+use aoc2022::visualize::{self, Target};
+use aoc2022::watch::{self, Snapshot};
+use crate::cli::{BenchArgs, Cli, Command, Format, ReportArgs, RunArgs};
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_logging(cli.log.as_deref());
+    let mut reporter: Box<dyn Reporter> = match &cli.command {
+        Command::Run { run, .. } | Command::All { run, .. } | Command::Watch { run, .. } => run.format.reporter(),
+        _ => Box::new(TextReporter),
+    };
+
+    let result = match cli.command {
+        Command::Run { day, input, visualize, run } => {
+            let parallel = ParallelOptions { jobs: 1, split_parts: false };
+            registry::find(day).and_then(|entry| {
+                // Read once, stdin can't be read again for the visualization
+                let input = match input.as_deref() {
+                    Some(arg) => Some(InputSource::Text(InputSource::from_arg(arg).read().map_err(|e| e.with_day(day))?)),
+                    None => None,
+                };
+                let passed = run_days(&[entry], input.as_ref(), &run, &parallel, reporter.as_mut())?;
+                if let Some(target) = visualize {
+                    visualize_day(entry, input.as_ref(), &Target::from_arg(&target))?;
+                }
+                Ok(passed)
+            })
+        }
+        Command::All { jobs, split_parts, run } => {
+            // With one job the parts run one after the other anyway
+            if split_parts && jobs <= 1 {
+                Cli::command().error(ErrorKind::ArgumentConflict, "--split-parts needs --jobs 2 or more").exit();
+            }
+            let entries: Vec<&DayEntry> = registry::DAYS.iter().collect();
+            let parallel = ParallelOptions { jobs: jobs as usize, split_parts };
+            run_days(&entries, None, &run, &parallel, reporter.as_mut())
+        }
+        Command::Watch { day, interval, run } => {
+            registry::find(day).and_then(|entry| watch_day(entry, interval, &run, reporter.as_mut()))
+        }
+        Command::Bench { day, bench } => {
+            let entries = match day {
+                Some(day) => registry::find(day).map(|x| vec![x]),
+                None => Ok(registry::DAYS.iter().collect()),
+            };
+            entries.and_then(|entries| bench_days(&entries, &bench, reporter.as_mut()))
+        }
+        Command::Compare { day, part, skip_example, timeout } => {
+            let entries = match day {
+                Some(day) => registry::find(day).map(|x| vec![x]),
+                None => Ok(registry::DAYS.iter().filter(|x| x.implementations.len() > 1).collect()),
+            };
+            let options = RunOptions {
+                part: part.and_then(Part::from_number),
+                run_example: !skip_example,
+                stop_on_mismatch: false,
+                timeout: timeout.map(|x| Duration::from_secs_f64(x.max(0.0))),
+                ..RunOptions::default()
+            };
+            entries.and_then(|entries| compare_days(&entries, &options))
+        }
+        Command::Fuzz { day, cases, size, seed, part } => {
+            let seed = seed.unwrap_or_else(generate::random_seed);
+            let options = FuzzOptions { cases, size, seed, part: part.and_then(Part::from_number) };
+            registry::find(day).and_then(|entry| fuzz_day(entry, &options))
+        }
+        Command::Gen { day, size, seed, output } => {
+            let seed = seed.unwrap_or_else(generate::random_seed);
+            registry::find(day).and_then(|entry| gen_day(entry, size, seed, output.as_deref()))
+        }
+        Command::Report { report } => report_days(&report),
+        Command::History { day, limit, threshold } => history::load(HISTORY_FILE).map(|entries| {
+            let entries: Vec<_> = entries.into_iter().filter(|x| day.is_none_or(|day| x.day == day)).collect();
+            if entries.is_empty() {
+                println!("No runs recorded in {} yet", HISTORY_FILE);
+            }
+            history::print_trends(&history::trends(&entries, threshold), limit);
+            true
+        }),
+        Command::List => {
+            for entry in registry::DAYS {
+                println!("Day {}: {}", entry.day, entry.title);
+            }
+            Ok(true)
+        }
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            reporter.error(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Silent unless asked for, solvers log under their day's name so one day can be turned on at a time
+fn init_logging(filter: Option<&str>) {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(log::LevelFilter::Off).format_timestamp(None);
+    if let Ok(filter) = std::env::var("RUST_LOG") {
+        builder.parse_filters(&filter);
+    }
+    if let Some(filter) = filter {
+        builder.parse_filters(filter);
+    }
+    builder.init();
+}
+
+// Runs each day, reporting errors and continuing so one bad input doesn't hide the rest.
+// Returns whether every day ran without errors or wrong answers.
+fn run_days(
+    entries: &[&'static DayEntry],
+    input: Option<&InputSource>,
+    args: &RunArgs,
+    parallel: &ParallelOptions,
+    reporter: &mut dyn Reporter,
+) -> error::Result<bool> {
+    let mut manifest = Manifest::load(MANIFEST_FILE)?;
+    let mut options = args.options();
+    if input.is_some() {
+        // A single file has no known answers to check
+        options.run_example = false;
+        options.run_full = true;
+    }
+
+    let days: Vec<DayRun> = entries
+        .iter()
+        .map(|entry| DayRun {
+            entry,
+            inputs: match input {
+                Some(source) => Inputs {
+                    examples: Vec::new(),
+                    full: source.clone(),
+                    full_expected: (None, None),
+                },
+                None => entry.inputs(&manifest),
+            },
+            options: options.clone(),
+        })
+        .collect();
+
+    let commit = history::current_commit();
+    let mut history_entries = Vec::new();
+    // Other inputs would make the timings incomparable
+    let keep_history = input.is_none() && history::comparable_timings(parallel.jobs);
+
+    let mut passed = true;
+    let mut recorded = false;
+    parallel::run(&days, parallel, reporter, |entry, result, reporter| match result {
+        Ok(results) => {
+            passed &= results.iter().all(|x| x.passed() != Some(false));
+            if keep_history {
+                history_entries.extend(history::entries(entry.day, &results, commit.as_deref()));
+            }
+            if args.record {
+                for (part, answer) in manifest.record(entry.day, &results) {
+                    reporter.recorded(entry.day, part, &answer);
+                    recorded = true;
+                }
+            }
+        }
+        Err(e) => {
+            reporter.error(&e);
+            passed = false;
+        }
+    });
+
+    if recorded {
+        manifest.save(MANIFEST_FILE)?;
+    }
+    history::append(HISTORY_FILE, &history_entries)?;
+
+    Ok(passed)
 }
 
-fn test_day16() -> std::io::Result<()> {
-    println!("<--------    Running Day 16    -------->");
-    run_with_test(&Day16, "input/day16e.txt", (1651, 1707), "input/day16.txt")
+// Runs the day every time one of its input files changes, then shows how the answers and timings
+// moved since the previous run. Rebuild loops like `cargo watch -x "run -- watch 20"` restart it
+// when the source changes, and the previous run is kept on disk so that diff still works.
+fn watch_day(entry: &DayEntry, interval: f64, args: &RunArgs, reporter: &mut dyn Reporter) -> error::Result<bool> {
+    let snapshot_path = watch::snapshot_path(entry.day);
+    let interval = Duration::from_secs_f64(interval.max(0.1));
+
+    loop {
+        let mut manifest = Manifest::load(MANIFEST_FILE)?;
+        let inputs = entry.inputs(&manifest);
+        let input_times = watch::input_times(&inputs);
+
+        match entry.run_inputs(&inputs, &args.options(), reporter) {
+            Ok(results) => {
+                let snapshot = Snapshot::new(&results);
+                if let (Format::Text, Some(previous)) = (args.format, Snapshot::load(&snapshot_path)?) {
+                    watch::print_diff(&previous, &snapshot);
+                }
+                snapshot.save(&snapshot_path)?;
+
+                if args.record {
+                    let recorded = manifest.record(entry.day, &results);
+                    for (part, answer) in recorded.iter() {
+                        reporter.recorded(entry.day, *part, answer);
+                    }
+                    if !recorded.is_empty() {
+                        manifest.save(MANIFEST_FILE)?;
+                    }
+                }
+            }
+            Err(e) => reporter.error(&e),
+        }
+
+        if args.format == Format::Text {
+            println!("Watching {} input files for changes...", input_times.len());
+            println!();
+        }
+        // Inputs are discovered again so new example files count as a change too
+        while watch::input_times(&entry.inputs(&manifest)) == input_times {
+            std::thread::sleep(interval);
+        }
+    }
 }
 
-fn test_day17() -> std::io::Result<()> {
-    println!("<--------    Running Day 17    -------->");
-    run_with_test(
-        &Day17,
-        "input/day17e.txt",
-        (3068, 1514285714288),
-        "input/day17.txt",
-    )
+fn compare_days(entries: &[&DayEntry], options: &RunOptions) -> error::Result<bool> {
+    let manifest = Manifest::load(MANIFEST_FILE)?;
+    let mut agreed = true;
+    for entry in entries {
+        let runs = entry.compare(options, &manifest);
+        agreed &= compare::print_comparison(entry.day, entry.title, &runs);
+    }
+
+    Ok(agreed)
 }
 
-fn test_day18() -> std::io::Result<()> {
-    println!("<--------    Running Day 18    -------->");
-    run_with_test(&Day18, "input/day18e.txt", (64, 58), "input/day18.txt")
+fn gen_day(entry: &DayEntry, size: usize, seed: u64, output: Option<&str>) -> error::Result<bool> {
+    let Some(generator) = entry.generator else {
+        println!("Day {} has no input generator", entry.day);
+        return Ok(true);
+    };
+    let content = generate::generate(generator, size, seed);
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, content).map_err(|e| error::Error::io(path, e))?;
+            println!("Wrote a day {} input of size {} from seed {} to {}", entry.day, size, seed, path);
+        }
+        // The seed goes to stderr so the input can be piped
+        None => {
+            print!("{}", content);
+            eprintln!("Seed {}", seed);
+        }
+    }
+
+    Ok(true)
 }
 
-fn test_day20() -> std::io::Result<()> {
-    println!("<--------    Running Day 20    -------->");
-    run_with_test_2(&Day20, "input/day20e.txt", (3, 0), "input/day20.txt")
+fn fuzz_day(entry: &DayEntry, options: &FuzzOptions) -> error::Result<bool> {
+    let Some(generator) = entry.generator else {
+        println!("Day {} has no input generator", entry.day);
+        return Ok(true);
+    };
+    let [reference, others @ ..] = entry.implementations else {
+        return Ok(true);
+    };
+    if others.is_empty() {
+        println!("Day {} has only one implementation", entry.day);
+        return Ok(true);
+    }
+
+    println!("<--------    Fuzzing Day {}: {}    -------->", entry.day, entry.title);
+    let names: Vec<&str> = others.iter().map(|x| x.name).collect();
+    println!(
+        "{} against {}, {} cases up to size {} from seed {}",
+        reference.name,
+        names.join(", "),
+        options.cases,
+        options.size,
+        options.seed
+    );
+
+    match fuzz::fuzz(entry, generator, options) {
+        Some(disagreement) => {
+            let path = fuzz::save(entry.day, &disagreement)?;
+            fuzz::print_disagreement(&disagreement, &path, options.seed);
+            Ok(false)
+        }
+        None => {
+            println!("No disagreements");
+            Ok(true)
+        }
+    }
+}
+
+// Runs the full inputs quietly, then writes one table covering every day
+fn report_days(args: &ReportArgs) -> error::Result<bool> {
+    let manifest = Manifest::load(MANIFEST_FILE)?;
+    let baseline = Baseline::load(&args.baseline)?;
+    let options = args.options();
+
+    let days: Vec<DayRun> = registry::DAYS
+        .iter()
+        .map(|entry| DayRun {
+            entry,
+            inputs: entry.inputs(&manifest),
+            options: options.clone(),
+        })
+        .collect();
+
+    let commit = history::current_commit();
+    let mut history_entries = Vec::new();
+    let keep_history = history::comparable_timings(args.jobs as usize);
+
+    let mut collector = ReportCollector::default();
+    let parallel = ParallelOptions { jobs: args.jobs as usize, split_parts: false };
+    parallel::run(&days, &parallel, &mut collector, |entry, result, reporter| match result {
+        Ok(results) if keep_history => history_entries.extend(history::entries(entry.day, &results, commit.as_deref())),
+        Ok(_) => {}
+        Err(e) => reporter.error(&e),
+    });
+    history::append(HISTORY_FILE, &history_entries)?;
+    collector.compare(&baseline);
+
+    let content = report::render(&collector.days, args.report_format());
+    match &args.output {
+        Some(path) => std::fs::write(path, content).map_err(|e| error::Error::io(path, e))?,
+        None => print!("{}", content),
+    }
+
+    Ok(collector.days.iter().all(|x| x.passed()))
+}
+
+fn visualize_day(entry: &DayEntry, input: Option<&InputSource>, target: &Target) -> error::Result<()> {
+    let source = match (input, entry.examples.first()) {
+        (Some(source), _) => source.clone(),
+        (None, Some(example)) => InputSource::File(input::example_input_path(example.name)),
+        (None, None) => InputSource::File(input::full_input_path(entry.day)),
+    };
+
+    match entry.visualize(&source)? {
+        Some(frames) => visualize::render(entry.day, &frames, target)?,
+        None => println!("Day {} has no visualization", entry.day),
+    }
+
+    Ok(())
+}
+
+fn bench_days(entries: &[&DayEntry], args: &BenchArgs, reporter: &mut dyn Reporter) -> error::Result<bool> {
+    let mut baseline = Baseline::load(&args.baseline)?;
+    let options = args.options();
+    let mut passed = true;
+
+    for entry in entries {
+        bench::print_header(entry.day, entry.title);
+        match entry.bench(&options) {
+            Ok(stages) => {
+                for (stage, stats) in stages {
+                    bench::print_stats(stage, &stats, baseline.get(entry.day, stage));
+                    if args.save {
+                        baseline.insert(entry.day, stage, stats);
+                    }
+                }
+            }
+            Err(e) => {
+                reporter.error(&e);
+                passed = false;
+            }
+        }
+        println!();
+    }
+
+    if args.save {
+        baseline.save(&args.baseline)?;
+    }
+
+    Ok(passed)
 }
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::answer::Answer;
+use crate::day::{InputKind, Part, PartResult};
+use crate::error::Result;
+use crate::store;
+
+pub const MANIFEST_FILE: &str = "answers.json";
+
+// Confirmed answers for each day's full input, keyed by day number
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Manifest {
+    days: BTreeMap<u8, DayAnswers>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct DayAnswers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part_1: Option<Answer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part_2: Option<Answer>,
+}
+
+impl DayAnswers {
+    fn part_mut(&mut self, part: Part) -> &mut Option<Answer> {
+        match part {
+            Part::One => &mut self.part_1,
+            Part::Two => &mut self.part_2,
+        }
+    }
+}
+
+impl Manifest {
+    // A missing manifest just has no answers yet
+    pub fn load(path: &str) -> Result<Manifest> {
+        Ok(store::load_json(path)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        store::save_json(path, self)
+    }
+
+    pub fn expected(&self, day: u8) -> (Option<Answer>, Option<Answer>) {
+        match self.days.get(&day) {
+            Some(answers) => (answers.part_1.clone(), answers.part_2.clone()),
+            None => (None, None),
+        }
+    }
+
+    // Records full input answers that aren't in the manifest yet, but only for parts where
+    // at least one example answer was checked and none were wrong. Recorded answers are never overwritten.
+    pub fn record(&mut self, day: u8, results: &[PartResult]) -> Vec<(Part, Answer)> {
+        let mut recorded = Vec::new();
+        for result in results.iter().filter(|x| x.input == InputKind::Full) {
+            let Ok(full_answer) = &result.answer else {
+                continue;
+            };
+            let checked: Vec<bool> = results
+                .iter()
+                .filter(|x| x.input.is_example() && x.part == result.part)
+                .filter_map(|x| x.passed())
+                .collect();
+            let confirmed = !checked.is_empty() && checked.iter().all(|x| *x);
+            if !confirmed {
+                continue;
+            }
+
+            let answer = self.days.entry(day).or_default().part_mut(result.part);
+            if answer.is_none() {
+                *answer = Some(full_answer.clone());
+                recorded.push((result.part, full_answer.clone()));
+            }
+        }
+
+        recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::*;
+    use crate::error::Error;
+
+    fn result(input: InputKind, part: Part, answer: i128, expected: Option<i128>) -> PartResult {
+        PartResult {
+            input,
+            part,
+            answer: Ok(answer.into()),
+            expected: expected.map(Answer::from),
+            elapsed: Duration::ZERO,
+            memory: None,
+        }
+    }
+
+    fn example(part: Part, answer: i128, expected: Option<i128>) -> PartResult {
+        result(InputKind::Example("day01e".to_string()), part, answer, expected)
+    }
+
+    #[test]
+    fn records_parts_whose_examples_passed() {
+        let mut manifest = Manifest::default();
+        let results = [
+            example(Part::One, 3, Some(3)),
+            result(InputKind::Full, Part::One, 30, None),
+            // Wrong
+            example(Part::Two, 4, Some(5)),
+            result(InputKind::Full, Part::Two, 40, None),
+        ];
+
+        assert_eq!(manifest.record(1, &results), vec![(Part::One, 30.into())]);
+        assert_eq!(manifest.expected(1), (Some(30.into()), None));
+    }
+
+    #[test]
+    fn needs_a_checked_example() {
+        let mut manifest = Manifest::default();
+        let results = [
+            example(Part::One, 3, None),
+            result(InputKind::Full, Part::One, 30, None),
+            result(InputKind::Full, Part::Two, 40, None),
+        ];
+
+        assert!(manifest.record(1, &results).is_empty());
+        assert_eq!(manifest.expected(1), (None, None));
+    }
+
+    #[test]
+    fn timed_out_examples_and_full_inputs_arent_recorded() {
+        let mut manifest = Manifest::default();
+        let mut timed_out_example = example(Part::One, 3, Some(3));
+        timed_out_example.answer = Err(Error::cancelled(None));
+        let mut timed_out_full = result(InputKind::Full, Part::Two, 40, None);
+        timed_out_full.answer = Err(Error::cancelled(None));
+        let results = [
+            timed_out_example,
+            result(InputKind::Full, Part::One, 30, None),
+            example(Part::Two, 4, Some(4)),
+            timed_out_full,
+        ];
+
+        assert!(manifest.record(1, &results).is_empty());
+    }
+
+    #[test]
+    fn never_overwrites() {
+        let mut manifest = Manifest::default();
+        manifest.record(1, &[example(Part::One, 3, Some(3)), result(InputKind::Full, Part::One, 30, None)]);
+
+        let results = [example(Part::One, 3, Some(3)), result(InputKind::Full, Part::One, 31, None)];
+        assert!(manifest.record(1, &results).is_empty());
+        assert_eq!(manifest.expected(1), (Some(30.into()), None));
+    }
+}
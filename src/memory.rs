@@ -0,0 +1,136 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use serde::Serialize;
+
+// Counts every allocation, but only when built with the count-alloc feature since it
+// costs a little on every allocation
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+pub struct CountingAllocator;
+
+// Per thread, so days running in parallel don't count each other's memory. Const and without
+// destructors, so using them can't allocate.
+thread_local! {
+    static CURRENT: Cell<i64> = const { Cell::new(0) };
+    static PEAK: Cell<i64> = const { Cell::new(0) };
+    static TOTAL: Cell<u64> = const { Cell::new(0) };
+    static COUNT: Cell<u64> = const { Cell::new(0) };
+}
+
+fn allocated(size: usize) {
+    let current = CURRENT.with(|x| {
+        x.set(x.get() + size as i64);
+        x.get()
+    });
+    PEAK.with(|x| x.set(x.get().max(current)));
+    TOTAL.with(|x| x.set(x.get() + size as u64));
+    COUNT.with(|x| x.set(x.get() + 1));
+}
+
+// Memory freed on another thread than it was allocated on can make CURRENT go negative,
+// it's only ever compared to itself on the same thread
+fn freed(size: usize) {
+    CURRENT.with(|x| x.set(x.get() - size as i64));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        freed(layout.size());
+    }
+
+    // Counted as freeing the old block and allocating the new one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct MemoryStats {
+    // Most memory held at once above what was held before, in bytes
+    pub peak_bytes: u64,
+    // Everything allocated along the way, even if it was freed again
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+impl Display for MemoryStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} peak, {} allocated in {} allocations",
+            Bytes(self.peak_bytes),
+            Bytes(self.total_bytes),
+            self.allocations
+        )
+    }
+}
+
+struct Bytes(u64);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+
+        let mut size = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.2} {}", size, UNITS[unit])
+    }
+}
+
+pub fn enabled() -> bool {
+    cfg!(feature = "count-alloc")
+}
+
+// Runs f and counts what it allocated on this thread, None unless built with count-alloc
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<MemoryStats>) {
+    if !enabled() {
+        return (f(), None);
+    }
+
+    let start = CURRENT.with(|x| x.get());
+    let previous_peak = PEAK.with(|x| x.replace(start));
+    let (total, count) = (TOTAL.with(|x| x.get()), COUNT.with(|x| x.get()));
+
+    let result = f();
+
+    let peak = PEAK.with(|x| x.replace(previous_peak.max(x.get())));
+    let stats = MemoryStats {
+        peak_bytes: (peak - start).max(0) as u64,
+        total_bytes: TOTAL.with(|x| x.get()) - total,
+        allocations: COUNT.with(|x| x.get()) - count,
+    };
+
+    (result, Some(stats))
+}
@@ -0,0 +1,214 @@
+use std::time::Duration;
+use serde::Serialize;
+use crate::answer::Answer;
+use crate::day::{InputKind, Part, PartResult};
+use crate::error::Error;
+use crate::memory::MemoryStats;
+
+// Receives the runner's progress as it happens, so long parts still show what already finished
+pub trait Reporter {
+    fn start_day(&mut self, day: u8, title: &str);
+
+    // memory is only measured when built with the count-alloc feature
+    fn parsed(&mut self, input: &InputKind, elapsed: Duration, memory: Option<MemoryStats>);
+
+    fn solved(&mut self, result: &PartResult);
+
+    fn error(&mut self, error: &Error);
+
+    // A new answer was saved to the answers manifest
+    fn recorded(&mut self, day: u8, part: Part, answer: &Answer);
+
+    fn finish_day(&mut self, results: &[PartResult]);
+}
+
+// Drops everything, for callers that only want the returned results
+pub struct QuietReporter;
+
+impl Reporter for QuietReporter {
+    fn start_day(&mut self, _day: u8, _title: &str) {}
+    fn parsed(&mut self, _input: &InputKind, _elapsed: Duration, _memory: Option<MemoryStats>) {}
+    fn solved(&mut self, _result: &PartResult) {}
+    fn error(&mut self, _error: &Error) {}
+    fn recorded(&mut self, _day: u8, _part: Part, _answer: &Answer) {}
+    fn finish_day(&mut self, _results: &[PartResult]) {}
+}
+
+// Human readable output, rings the terminal bell when a day finishes or an answer is wrong
+pub struct TextReporter;
+
+impl TextReporter {
+    fn label(input: &InputKind) -> String {
+        match input {
+            InputKind::Example(name) => format!("Example {}", name),
+            InputKind::Full => "Full".to_string(),
+        }
+    }
+}
+
+impl Reporter for TextReporter {
+    fn start_day(&mut self, day: u8, title: &str) {
+        println!("<--------    Running Day {}: {}    -------->", day, title);
+    }
+
+    fn parsed(&mut self, input: &InputKind, elapsed: Duration, memory: Option<MemoryStats>) {
+        println!("{} - Parsed file.", Self::label(input));
+        println!("Elapsed Time: {:.2?}", elapsed);
+        if let Some(memory) = memory {
+            println!("Memory: {}", memory);
+        }
+        println!();
+    }
+
+    fn solved(&mut self, result: &PartResult) {
+        let label = format!("{} - Part {}", Self::label(&result.input), result.part.number());
+        match &result.answer {
+            Ok(answer) if answer.is_multiline() => println!("{}:\n{}", label, answer),
+            Ok(answer) => println!("{}: {}", label, answer),
+            Err(Error::Cancelled { limit: Some(limit), .. }) => println!("{}: timed out after {:.2?}\x07", label, limit),
+            Err(e) => println!("{}: {}\x07", label, e),
+        }
+        println!("Elapsed Time: {:.2?}", result.elapsed);
+        if let Some(memory) = &result.memory {
+            println!("Memory: {}", memory);
+        }
+        println!();
+
+        if let (Ok(answer), Some(false), Some(expected)) = (&result.answer, result.passed(), &result.expected) {
+            let message = match result.input {
+                InputKind::Example(_) => "Error! Expected answer",
+                InputKind::Full => "Regression! Recorded answer",
+            };
+            println!(
+                "{}\n\"Part {}: {}\", but got\n\"Part {}: {}\"\x07",
+                message,
+                result.part.number(), expected,
+                result.part.number(), answer
+            );
+            println!();
+        }
+    }
+
+    fn error(&mut self, error: &Error) {
+        eprintln!("Error: {}", error);
+        println!();
+    }
+
+    fn recorded(&mut self, day: u8, part: Part, answer: &Answer) {
+        println!("Recorded Day {} Part {}: {}", day, part.number(), answer);
+    }
+
+    fn finish_day(&mut self, results: &[PartResult]) {
+        // Wrong answers already rang
+        if results.iter().all(|x| x.passed() != Some(false)) {
+            print!("\x07");
+        }
+    }
+}
+
+// One JSON object per line for scripts: a "part" record per part and input, plus
+// "error" and "recorded" records. Timings are in nanoseconds. A part that ran out of time
+// has an error instead of an answer.
+#[derive(Default)]
+pub struct JsonReporter {
+    day: Option<u8>,
+    title: Option<String>,
+    parse_times: Vec<(InputKind, Duration, Option<MemoryStats>)>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Part {
+        day: Option<u8>,
+        title: Option<&'a str>,
+        input: &'static str,
+        // The example's name, e.g. day16e
+        example: Option<&'a str>,
+        part: u8,
+        answer: Option<&'a Answer>,
+        error: Option<String>,
+        expected: Option<&'a Answer>,
+        passed: Option<bool>,
+        parse_ns: Option<u64>,
+        elapsed_ns: u64,
+        // Only with the count-alloc feature
+        parse_memory: Option<MemoryStats>,
+        memory: Option<MemoryStats>,
+    },
+    Error {
+        day: Option<u8>,
+        message: String,
+    },
+    Recorded {
+        day: u8,
+        part: u8,
+        answer: &'a Answer,
+    },
+}
+
+impl JsonReporter {
+    fn emit(record: &Record) {
+        // Records only hold strings, numbers and answers, which always serialize
+        println!("{}", serde_json::to_string(record).unwrap());
+    }
+}
+
+fn input_name(input: &InputKind) -> (&'static str, Option<&str>) {
+    match input {
+        InputKind::Example(name) => ("example", Some(name)),
+        InputKind::Full => ("full", None),
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn start_day(&mut self, day: u8, title: &str) {
+        *self = JsonReporter {
+            day: Some(day),
+            title: Some(title.to_string()),
+            ..JsonReporter::default()
+        };
+    }
+
+    fn parsed(&mut self, input: &InputKind, elapsed: Duration, memory: Option<MemoryStats>) {
+        self.parse_times.push((input.clone(), elapsed, memory));
+    }
+
+    fn solved(&mut self, result: &PartResult) {
+        let parse = self.parse_times.iter().find(|x| x.0 == result.input);
+        let (input, example) = input_name(&result.input);
+
+        Self::emit(&Record::Part {
+            day: self.day,
+            title: self.title.as_deref(),
+            input,
+            example,
+            part: result.part.number(),
+            answer: result.answer.as_ref().ok(),
+            error: result.answer.as_ref().err().map(|e| e.to_string()),
+            expected: result.expected.as_ref(),
+            passed: result.passed(),
+            parse_ns: parse.map(|x| x.1.as_nanos() as u64),
+            elapsed_ns: result.elapsed.as_nanos() as u64,
+            parse_memory: parse.and_then(|x| x.2),
+            memory: result.memory,
+        });
+    }
+
+    fn error(&mut self, error: &Error) {
+        Self::emit(&Record::Error {
+            day: self.day,
+            message: error.to_string(),
+        });
+    }
+
+    fn recorded(&mut self, day: u8, part: Part, answer: &Answer) {
+        Self::emit(&Record::Recorded {
+            day,
+            part: part.number(),
+            answer,
+        });
+    }
+
+    fn finish_day(&mut self, _results: &[PartResult]) {}
+}
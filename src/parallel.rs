@@ -0,0 +1,302 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use crate::answer::Answer;
+use crate::day::{InputKind, Inputs, Part, PartResult, RunOptions};
+use crate::error::{Error, Result};
+use crate::memory::MemoryStats;
+use crate::output::Reporter;
+use crate::registry::DayEntry;
+
+pub struct DayRun {
+    pub entry: &'static DayEntry,
+    pub inputs: Inputs,
+    pub options: RunOptions,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ParallelOptions {
+    // Worker threads, 1 runs everything on this thread with live output
+    pub jobs: usize,
+    // Runs part 1 and part 2 of a day as separate tasks, each checking its own examples
+    pub split_parts: bool,
+}
+
+// One worker's share of a day
+struct Task {
+    day: usize,
+    options: RunOptions,
+}
+
+// Holds a task's output until every day before it has been reported
+#[derive(Default)]
+struct BufferedReporter {
+    events: Vec<Event>,
+}
+
+enum Event {
+    Parsed(InputKind, Duration, Option<MemoryStats>),
+    Solved(PartResult),
+    Error(Error),
+    Recorded(u8, Part, Answer),
+}
+
+impl Reporter for BufferedReporter {
+    // The merged day is started and finished when it's replayed
+    fn start_day(&mut self, _day: u8, _title: &str) {}
+
+    fn parsed(&mut self, input: &InputKind, elapsed: Duration, memory: Option<MemoryStats>) {
+        self.events.push(Event::Parsed(input.clone(), elapsed, memory));
+    }
+
+    fn solved(&mut self, result: &PartResult) {
+        self.events.push(Event::Solved(result.clone()));
+    }
+
+    fn error(&mut self, error: &Error) {
+        self.events.push(Event::Error(error.clone()));
+    }
+
+    fn recorded(&mut self, day: u8, part: Part, answer: &Answer) {
+        self.events.push(Event::Recorded(day, part, answer.clone()));
+    }
+
+    fn finish_day(&mut self, _results: &[PartResult]) {}
+}
+
+// Runs the days on a pool of worker threads, reporting each one in the given order once it
+// and every day before it have finished. `done` gets each day's results in the same order.
+pub fn run(
+    days: &[DayRun],
+    options: &ParallelOptions,
+    reporter: &mut dyn Reporter,
+    mut done: impl FnMut(&DayEntry, Result<Vec<PartResult>>, &mut dyn Reporter),
+) {
+    if options.jobs <= 1 {
+        for day in days {
+            let result = day.entry.run_inputs(&day.inputs, &day.options, reporter);
+            done(day.entry, result, reporter);
+        }
+        return;
+    }
+
+    let mut tasks = Vec::new();
+    for (index, day) in days.iter().enumerate() {
+        match (options.split_parts, day.options.part) {
+            (true, None) => {
+                for part in Part::ALL {
+                    let options = RunOptions { part: Some(part), ..day.options.clone() };
+                    tasks.push(Task { day: index, options });
+                }
+            }
+            _ => tasks.push(Task { day: index, options: day.options.clone() }),
+        }
+    }
+
+    let next_task = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..options.jobs.min(tasks.len()) {
+            let sender = sender.clone();
+            let (tasks, next_task) = (&tasks, &next_task);
+            scope.spawn(move || loop {
+                let index = next_task.fetch_add(1, Ordering::Relaxed);
+                let Some(task) = tasks.get(index) else {
+                    break;
+                };
+
+                let day = &days[task.day];
+                let mut buffer = BufferedReporter::default();
+                let result = day.entry.solver().run(&day.inputs, &task.options, &mut buffer).map_err(|e| e.with_day(day.entry.day));
+                // The receiver only goes away if the main thread panicked
+                if sender.send((index, buffer, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished: Vec<Option<(BufferedReporter, Result<Vec<PartResult>>)>> = tasks.iter().map(|_| None).collect();
+        let mut next_day = 0;
+        for (index, buffer, result) in receiver {
+            finished[index] = Some((buffer, result));
+
+            // Report every day that's now complete and next in line
+            while next_day < days.len() {
+                let task_indexes: Vec<usize> = (0..tasks.len()).filter(|x| tasks[*x].day == next_day).collect();
+                if task_indexes.iter().any(|x| finished[*x].is_none()) {
+                    break;
+                }
+
+                let day_tasks = task_indexes.iter().map(|x| finished[*x].take().unwrap()).collect();
+                let entry = days[next_day].entry;
+                let result = replay(entry, day_tasks, reporter);
+                done(entry, result, reporter);
+                next_day += 1;
+            }
+        }
+    });
+}
+
+// Reports a day's tasks as if they ran in one go, parsing is only shown once
+fn replay(
+    entry: &DayEntry,
+    day_tasks: Vec<(BufferedReporter, Result<Vec<PartResult>>)>,
+    reporter: &mut dyn Reporter,
+) -> Result<Vec<PartResult>> {
+    reporter.start_day(entry.day, entry.title);
+
+    let mut results = Vec::new();
+    let mut error = None;
+    for (index, (buffer, result)) in day_tasks.into_iter().enumerate() {
+        for event in buffer.events {
+            match event {
+                Event::Parsed(input, elapsed, memory) if index == 0 => reporter.parsed(&input, elapsed, memory),
+                Event::Parsed(..) => {}
+                Event::Solved(result) => reporter.solved(&result),
+                Event::Error(error) => reporter.error(&error),
+                Event::Recorded(day, part, answer) => reporter.recorded(day, part, &answer),
+            }
+        }
+
+        match result {
+            Ok(task_results) => results.extend(task_results),
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        }
+    }
+
+    match error {
+        Some(e) => Err(e),
+        None => {
+            reporter.finish_day(&results);
+            Ok(results)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day::Day;
+    use crate::input::InputSource;
+    use crate::registry::Implementation;
+
+    // Takes as many milliseconds as its input says, so later days can finish first
+    struct Sleep;
+
+    impl Day for Sleep {
+        type Input = u64;
+
+        fn parse_file(&self, file_content: String) -> Result<u64> {
+            file_content.parse().map_err(|_| Error::parse(1, &file_content, "expected milliseconds"))
+        }
+
+        fn part_1(&self, data: &u64) -> Result<Answer> {
+            std::thread::sleep(Duration::from_millis(*data));
+            Ok((*data as i128).into())
+        }
+
+        fn part_2(&self, data: &u64) -> Result<Answer> {
+            std::thread::sleep(Duration::from_millis(*data));
+            Ok((*data as i128 * 2).into())
+        }
+    }
+
+    const ENTRIES: &[DayEntry] = &[
+        DayEntry { day: 1, title: "Slow", examples: &[], implementations: &[Implementation { name: "sleep", solver: &Sleep }], generator: None },
+        DayEntry { day: 2, title: "Fast", examples: &[], implementations: &[Implementation { name: "sleep", solver: &Sleep }], generator: None },
+        DayEntry { day: 3, title: "Medium", examples: &[], implementations: &[Implementation { name: "sleep", solver: &Sleep }], generator: None },
+    ];
+
+    // Everything but the timings
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl Reporter for Recorder {
+        fn start_day(&mut self, day: u8, _title: &str) {
+            self.events.push(format!("start {}", day));
+        }
+
+        fn parsed(&mut self, input: &InputKind, _elapsed: Duration, _memory: Option<MemoryStats>) {
+            self.events.push(format!("parsed {:?}", input));
+        }
+
+        fn solved(&mut self, result: &PartResult) {
+            self.events.push(format!("part {} {:?}", result.part.number(), result.answer.as_ref().ok()));
+        }
+
+        fn error(&mut self, error: &Error) {
+            self.events.push(format!("error {}", error));
+        }
+
+        fn recorded(&mut self, day: u8, part: Part, answer: &Answer) {
+            self.events.push(format!("recorded {} {} {}", day, part.number(), answer));
+        }
+
+        fn finish_day(&mut self, results: &[PartResult]) {
+            self.events.push(format!("finish {}", results.len()));
+        }
+    }
+
+    fn days(millis: &[u64]) -> Vec<DayRun> {
+        ENTRIES
+            .iter()
+            .zip(millis)
+            .map(|(entry, millis)| DayRun {
+                entry,
+                inputs: Inputs {
+                    examples: Vec::new(),
+                    full: InputSource::Text(millis.to_string()),
+                    full_expected: (None, None),
+                },
+                options: RunOptions { run_example: false, ..RunOptions::default() },
+            })
+            .collect()
+    }
+
+    // What was reported and the days done was called for, in order
+    fn events(days: &[DayRun], options: &ParallelOptions) -> (Vec<String>, Vec<(u8, usize)>) {
+        let mut reporter = Recorder::default();
+        let mut done = Vec::new();
+        run(days, options, &mut reporter, |entry, result, _| done.push((entry.day, result.unwrap().len())));
+        (reporter.events, done)
+    }
+
+    #[test]
+    fn reports_days_in_order_whichever_finishes_first() {
+        let days = days(&[60, 0, 20]);
+        let sequential = events(&days, &ParallelOptions { jobs: 1, split_parts: false });
+        assert_eq!(sequential.1, vec![(1, 2), (2, 2), (3, 2)]);
+
+        assert_eq!(events(&days, &ParallelOptions { jobs: 3, split_parts: false }), sequential);
+    }
+
+    #[test]
+    fn split_parts_report_like_one_day() {
+        let days = days(&[40, 0, 20]);
+        let sequential = events(&days, &ParallelOptions { jobs: 1, split_parts: false });
+
+        // Each part parses the input, only the first one shows
+        assert_eq!(events(&days, &ParallelOptions { jobs: 4, split_parts: true }), sequential);
+        assert_eq!(
+            sequential.0[..5],
+            ["start 1", "parsed Full", "part 1 Some(Number(40))", "part 2 Some(Number(80))", "finish 2"]
+        );
+    }
+
+    #[test]
+    fn a_failed_part_fails_its_day() {
+        let mut days = days(&[0, 0, 0]);
+        days[1].inputs.full = InputSource::Text("not a number".to_string());
+
+        let mut done = Vec::new();
+        run(&days, &ParallelOptions { jobs: 2, split_parts: true }, &mut Recorder::default(), |entry, result, _| {
+            done.push((entry.day, result.is_ok()))
+        });
+        assert_eq!(done, vec![(1, true), (2, false), (3, true)]);
+    }
+}
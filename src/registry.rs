@@ -0,0 +1,184 @@
+use crate::answer::Answer;
+use crate::bench::{BenchOptions, Stage, Stats};
+use crate::day::{ExampleInput, Inputs, Part, PartResult, RunOptions, Solution};
+use crate::generate::Generator;
+use crate::input::{self, InputSource};
+use crate::manifest::Manifest;
+use crate::compare::ImplementationRun;
+use crate::output::{QuietReporter, Reporter};
+use crate::visualize::Frame;
+use crate::error::{Error, Result};
+use crate::{day16, day17, day18, day20};
+
+pub struct DayEntry {
+    pub day: u8,
+    pub title: &'static str,
+    // Examples with known answers, run in this order. Other input/dayNNe*.txt files run unchecked after them.
+    pub examples: &'static [ExampleEntry],
+    // Every command but compare and fuzz runs just the first one, those check the rest against it
+    pub implementations: &'static [Implementation],
+    // Random inputs for fuzzing the implementations against each other
+    pub generator: Option<Generator>,
+}
+
+pub struct Implementation {
+    pub name: &'static str,
+    pub solver: &'static (dyn Solution + Sync),
+}
+
+// An example read from input/<name>.txt
+pub struct ExampleEntry {
+    pub name: &'static str,
+    // None for parts without a known answer
+    pub expected: (Option<Answer>, Option<Answer>),
+}
+
+pub const DAYS: &[DayEntry] = &[
+    DayEntry {
+        day: 16,
+        title: "Proboscidea Volcanium",
+        examples: &[
+            ExampleEntry {
+                name: "day16e",
+                expected: (Some(Answer::Number(1651)), Some(Answer::Number(1707))),
+            },
+            // The elephant has to take both far valves while the player takes the near one
+            ExampleEntry {
+                name: "day16e2",
+                expected: (Some(Answer::Number(72)), Some(Answer::Number(66))),
+            },
+        ],
+        implementations: &[
+            Implementation { name: "dfs", solver: &day16::Day16 },
+            Implementation { name: "bitmask-dp", solver: &day16::Day16Bitmask },
+        ],
+        generator: Some(day16::generate),
+    },
+    DayEntry {
+        day: 17,
+        title: "Pyroclastic Flow",
+        examples: &[ExampleEntry {
+            name: "day17e",
+            expected: (Some(Answer::Number(3068)), Some(Answer::Number(1514285714288))),
+        }],
+        implementations: &[Implementation { name: "simulation", solver: &day17::Day17 }],
+        generator: Some(day17::generate),
+    },
+    DayEntry {
+        day: 18,
+        title: "Boiling Boulders",
+        examples: &[
+            ExampleEntry {
+                name: "day18e",
+                expected: (Some(Answer::Number(64)), Some(Answer::Number(58))),
+            },
+            // The two cube example from the puzzle text
+            ExampleEntry {
+                name: "day18e2",
+                expected: (Some(Answer::Number(10)), Some(Answer::Number(10))),
+            },
+        ],
+        implementations: &[
+            Implementation { name: "flood-fill", solver: &day18::Day18 },
+            Implementation { name: "voxel-grid", solver: &day18::Day18Grid },
+        ],
+        generator: Some(day18::generate),
+    },
+    DayEntry {
+        day: 20,
+        title: "Grove Positioning System",
+        examples: &[ExampleEntry {
+            name: "day20e",
+            expected: (Some(Answer::Number(3)), Some(Answer::Number(1623178306))),
+        }],
+        implementations: &[
+            Implementation { name: "linked-list", solver: &day20::Day20 },
+            Implementation { name: "index-vec", solver: &day20::Day20Vec },
+        ],
+        generator: Some(day20::generate),
+    },
+];
+
+pub fn find(day: u8) -> Result<&'static DayEntry> {
+    DAYS.iter().find(|entry| entry.day == day).ok_or_else(|| Error::UnknownDay {
+        day,
+        available: DAYS.iter().map(|x| x.day).collect(),
+    })
+}
+
+impl DayEntry {
+    pub fn solver(&self) -> &'static (dyn Solution + Sync) {
+        self.implementations[0].solver
+    }
+
+    // Inputs follow the input/dayNN.txt and input/dayNNe*.txt naming
+    pub fn inputs(&self, manifest: &Manifest) -> Inputs {
+        // Declared examples run even when their file is missing, so reading it says which file is needed
+        let mut examples: Vec<ExampleInput> = self
+            .examples
+            .iter()
+            .map(|x| ExampleInput {
+                name: x.name.to_string(),
+                source: InputSource::File(input::example_input_path(x.name)),
+                expected: x.expected.clone(),
+            })
+            .collect();
+        for (name, path) in input::discover_examples(self.day) {
+            if !examples.iter().any(|x| x.name == name) {
+                examples.push(ExampleInput {
+                    name,
+                    source: InputSource::File(path),
+                    expected: (None, None),
+                });
+            }
+        }
+
+        Inputs {
+            examples,
+            full: InputSource::File(input::full_input_path(self.day)),
+            full_expected: manifest.expected(self.day),
+        }
+    }
+
+    pub fn run(&self, options: &RunOptions, manifest: &Manifest, reporter: &mut dyn Reporter) -> Result<Vec<PartResult>> {
+        self.run_inputs(&self.inputs(manifest), options, reporter)
+    }
+
+    // Runs other inputs, e.g. one given on the command line
+    pub fn run_inputs(&self, inputs: &Inputs, options: &RunOptions, reporter: &mut dyn Reporter) -> Result<Vec<PartResult>> {
+        reporter.start_day(self.day, self.title);
+        let results = self.solver().run(inputs, options, reporter).map_err(|e| e.with_day(self.day))?;
+        reporter.finish_day(&results);
+
+        Ok(results)
+    }
+
+    // Runs every implementation on the same inputs, one after the other so they don't slow each other down
+    pub fn compare(&self, options: &RunOptions, manifest: &Manifest) -> Vec<ImplementationRun> {
+        let inputs = self.inputs(manifest);
+        self.implementations
+            .iter()
+            .map(|implementation| ImplementationRun {
+                name: implementation.name,
+                result: implementation.solver.run(&inputs, options, &mut QuietReporter).map_err(|e| e.with_day(self.day)),
+            })
+            .collect()
+    }
+
+    // Frames of the day's visualization for an input, None when the day doesn't have one
+    pub fn visualize(&self, source: &InputSource) -> Result<Option<Vec<Frame>>> {
+        let file_content = source.read().map_err(|e| e.with_day(self.day))?;
+        self.solver().visualize(&file_content).map_err(|e| e.with_day(self.day))
+    }
+
+    // Solves one part of any input, e.g. a generated one, without printing anything
+    pub fn solve(&self, file_content: &str, part: Part) -> Result<Answer> {
+        self.solver().solve(file_content, part).map_err(|e| e.with_day(self.day))
+    }
+
+    // Times parsing and each part on the full input
+    pub fn bench(&self, options: &BenchOptions) -> Result<Vec<(Stage, Stats)>> {
+        let file_content = InputSource::File(input::full_input_path(self.day)).read().map_err(|e| e.with_day(self.day))?;
+        self.solver().bench(&file_content, options).map_err(|e| e.with_day(self.day))
+    }
+}
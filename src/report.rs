@@ -0,0 +1,257 @@
+use std::time::Duration;
+use crate::answer::Answer;
+use crate::bench::{self, Baseline, Stage};
+use crate::day::{InputKind, Part, PartResult};
+use crate::error::Error;
+use crate::memory::MemoryStats;
+use crate::output::Reporter;
+
+// One table row, built from the full input run of a day
+pub struct DayReport {
+    pub day: u8,
+    pub title: String,
+    pub parse: Option<Timing>,
+    pub parts: Vec<PartReport>,
+    pub error: Option<String>,
+}
+
+pub struct PartReport {
+    pub part: Part,
+    pub answer: Answer,
+    // Recorded in the answers manifest
+    pub expected: Option<Answer>,
+    pub time: Timing,
+}
+
+#[derive(Copy, Clone)]
+pub struct Timing {
+    pub elapsed: Duration,
+    // Median from the bench baseline, if the day was benched
+    pub baseline: Option<Duration>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl DayReport {
+    fn part(&self, part: Part) -> Option<&PartReport> {
+        self.parts.iter().find(|x| x.part == part)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.parts.iter().all(|x| x.passed() != Some(false))
+    }
+}
+
+impl PartReport {
+    pub fn passed(&self) -> Option<bool> {
+        self.expected.as_ref().map(|x| *x == self.answer)
+    }
+}
+
+impl Timing {
+    fn describe(&self) -> String {
+        let comparison = self.baseline.map(|baseline| bench::speed_change(baseline, self.elapsed));
+
+        match comparison {
+            Some(comparison) => format!("{:.2?} ({} than baseline)", self.elapsed, comparison),
+            None => format!("{:.2?}", self.elapsed),
+        }
+    }
+}
+
+// Builds the report rows from what the runner reports, only the full input is kept
+#[derive(Default)]
+pub struct ReportCollector {
+    pub days: Vec<DayReport>,
+}
+
+impl ReportCollector {
+    // Fills in the timings from the bench baseline once every day has run
+    pub fn compare(&mut self, baseline: &Baseline) {
+        for day in self.days.iter_mut() {
+            if let Some(parse) = day.parse.as_mut() {
+                parse.baseline = baseline.get(day.day, Stage::Parse).map(|x| x.median);
+            }
+            for part in day.parts.iter_mut() {
+                part.time.baseline = baseline.get(day.day, Stage::Part(part.part)).map(|x| x.median);
+            }
+        }
+    }
+}
+
+impl Reporter for ReportCollector {
+    fn start_day(&mut self, day: u8, title: &str) {
+        self.days.push(DayReport {
+            day,
+            title: title.to_string(),
+            parse: None,
+            parts: Vec::new(),
+            error: None,
+        });
+    }
+
+    fn parsed(&mut self, input: &InputKind, elapsed: Duration, _memory: Option<MemoryStats>) {
+        if let (InputKind::Full, Some(day)) = (input, self.days.last_mut()) {
+            day.parse = Some(Timing { elapsed, baseline: None });
+        }
+    }
+
+    // A part that ran out of time shows its error in its answer cell
+    fn solved(&mut self, result: &PartResult) {
+        if let (InputKind::Full, Some(day)) = (&result.input, self.days.last_mut()) {
+            let answer = match &result.answer {
+                Ok(answer) => answer.clone(),
+                Err(e) => {
+                    day.error = Some(e.to_string());
+                    return;
+                }
+            };
+            day.parts.push(PartReport {
+                part: result.part,
+                answer,
+                expected: result.expected.clone(),
+                time: Timing { elapsed: result.elapsed, baseline: None },
+            });
+        }
+    }
+
+    fn error(&mut self, error: &Error) {
+        if let Some(day) = self.days.last_mut() {
+            day.error = Some(error.to_string());
+        }
+    }
+
+    fn recorded(&mut self, _day: u8, _part: Part, _answer: &Answer) {}
+
+    fn finish_day(&mut self, _results: &[PartResult]) {}
+}
+
+// Text for a part's answer cell, before escaping
+fn answer_cell(part: Option<&PartReport>) -> String {
+    let Some(part) = part else {
+        return "-".to_string();
+    };
+
+    match (part.passed(), &part.expected) {
+        (Some(true), _) => format!("{} ✓", part.answer),
+        (Some(false), Some(expected)) => format!("{} ✗ expected {}", part.answer, expected),
+        _ => format!("{} (unchecked)", part.answer),
+    }
+}
+
+fn time_cell(timing: Option<Timing>) -> String {
+    timing.map_or("-".to_string(), |x| x.describe())
+}
+
+fn summary(days: &[DayReport]) -> String {
+    let parts: Vec<&PartReport> = days.iter().flat_map(|x| x.parts.iter()).collect();
+    let correct = parts.iter().filter(|x| x.passed() == Some(true)).count();
+    let wrong = parts.iter().filter(|x| x.passed() == Some(false)).count();
+    let errors = days.iter().filter(|x| x.error.is_some()).count();
+
+    format!(
+        "{} days, {} parts solved: {} match answers.json, {} don't, {} unchecked. {} days failed to run.",
+        days.len(),
+        parts.len(),
+        correct,
+        wrong,
+        parts.len() - correct - wrong,
+        errors
+    )
+}
+
+pub fn render(days: &[DayReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => markdown(days),
+        ReportFormat::Html => html(days),
+    }
+}
+
+const HEADERS: [&str; 7] = ["Day", "Title", "Part 1", "Part 2", "Parse", "Part 1 Time", "Part 2 Time"];
+
+fn rows(days: &[DayReport]) -> Vec<[String; 7]> {
+    days.iter()
+        .map(|day| {
+            let (part_1, part_2) = (day.part(Part::One), day.part(Part::Two));
+            // Parts that never got an answer show why
+            let answer = |part: Option<&PartReport>| match (part, &day.error) {
+                (None, Some(error)) => format!("Error: {}", error),
+                _ => answer_cell(part),
+            };
+            let (answer_1, answer_2) = (answer(part_1), answer(part_2));
+
+            [
+                day.day.to_string(),
+                day.title.clone(),
+                answer_1,
+                answer_2,
+                time_cell(day.parse),
+                time_cell(part_1.map(|x| x.time)),
+                time_cell(part_2.map(|x| x.time)),
+            ]
+        })
+        .collect()
+}
+
+fn markdown(days: &[DayReport]) -> String {
+    // Pipes would end the cell and newlines the row
+    fn escape(text: &str) -> String {
+        text.replace('|', "\\|").replace('\n', "<br>")
+    }
+
+    let mut result = String::from("# Advent of Code 2022\n\n");
+    result += &summary(days);
+    result += "\n\n";
+    result += &format!("| {} |\n", HEADERS.join(" | "));
+    result += &format!("|{}\n", "---|".repeat(HEADERS.len()));
+    for row in rows(days) {
+        let cells: Vec<String> = row.iter().map(|x| escape(x)).collect();
+        result += &format!("| {} |\n", cells.join(" | "));
+    }
+
+    result
+}
+
+fn html(days: &[DayReport]) -> String {
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\n', "<br>")
+    }
+
+    let mut result = String::from(concat!(
+        "<!DOCTYPE html>\n",
+        "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Advent of Code 2022</title>\n",
+        "<style>\n",
+        "body { font-family: sans-serif; }\n",
+        "table { border-collapse: collapse; }\n",
+        "th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }\n",
+        "tr.failed { background: #fdd; }\n",
+        "</style>\n</head>\n<body>\n<h1>Advent of Code 2022</h1>\n",
+    ));
+    result += &format!("<p>{}</p>\n<table>\n<tr>", escape(&summary(days)));
+    for header in HEADERS {
+        result += &format!("<th>{}</th>", header);
+    }
+    result += "</tr>\n";
+
+    for (day, row) in days.iter().zip(rows(days)) {
+        match day.passed() {
+            true => result += "<tr>",
+            false => result += "<tr class=\"failed\">",
+        }
+        for cell in row.iter() {
+            result += &format!("<td>{}</td>", escape(cell));
+        }
+        result += "</tr>\n";
+    }
+    result += "</table>\n</body>\n</html>\n";
+
+    result
+}
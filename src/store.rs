@@ -0,0 +1,33 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::error::{Error, Result};
+
+// The files runs keep for later ones: answers, baselines, history and watch snapshots
+
+// None when there's no file yet
+pub fn read(path: &str) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::io(path, e)),
+    }
+}
+
+// For a file in a directory that may not exist yet
+pub fn create_parent(path: &str) -> Result<()> {
+    match std::path::Path::new(path).parent() {
+        Some(parent) => std::fs::create_dir_all(parent).map_err(|e| Error::io(path, e)),
+        None => Ok(()),
+    }
+}
+
+pub fn load_json<T: DeserializeOwned>(path: &str) -> Result<Option<T>> {
+    read(path)?.map(|content| serde_json::from_str(&content).map_err(|e| Error::json(path, e))).transpose()
+}
+
+pub fn save_json<T: Serialize>(path: &str, value: &T) -> Result<()> {
+    create_parent(path)?;
+    let content = serde_json::to_string_pretty(value).map_err(|e| Error::json(path, e))?;
+
+    std::fs::write(path, content + "\n").map_err(|e| Error::io(path, e))
+}
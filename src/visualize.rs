@@ -0,0 +1,65 @@
+use std::fmt::{Display, Formatter};
+use crate::error::{Error, Result};
+
+// One picture of a solver's state, plain text so it works in any terminal
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub caption: String,
+    pub text: String,
+}
+
+impl Frame {
+    pub fn new(caption: impl Into<String>, text: impl Into<String>) -> Frame {
+        Frame {
+            caption: caption.into(),
+            text: text.into(),
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.caption)?;
+        write!(f, "{}", self.text.trim_end())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Target {
+    Terminal,
+    // One file per frame, under a folder for the day
+    Dir(String),
+}
+
+impl Target {
+    // "-" prints to the terminal, like it reads stdin for --input
+    pub fn from_arg(arg: &str) -> Target {
+        match arg {
+            "-" => Target::Terminal,
+            dir => Target::Dir(dir.to_string()),
+        }
+    }
+}
+
+pub fn render(day: u8, frames: &[Frame], target: &Target) -> Result<()> {
+    match target {
+        Target::Terminal => {
+            println!("<--------    Visualizing Day {}    -------->", day);
+            for frame in frames {
+                println!("{}", frame);
+                println!();
+            }
+        }
+        Target::Dir(dir) => {
+            let dir = format!("{}/day{:02}", dir, day);
+            std::fs::create_dir_all(&dir).map_err(|e| Error::io(&dir, e))?;
+            for (i, frame) in frames.iter().enumerate() {
+                let path = format!("{}/frame{:04}.txt", dir, i + 1);
+                std::fs::write(&path, format!("{}\n", frame)).map_err(|e| Error::io(&path, e))?;
+            }
+            println!("Wrote {} frames to {}", frames.len(), dir);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use crate::answer::Answer;
+use crate::bench;
+use crate::day::{InputKind, Inputs, PartResult};
+use crate::error::Result;
+use crate::input::InputSource;
+use crate::store;
+
+// The previous run lives under target/ so it survives the rebuild that restarts watch
+pub fn snapshot_path(day: u8) -> String {
+    format!("target/watch/day{:02}.json", day)
+}
+
+// Answers and timings from one watched run, compared against the next one
+#[derive(Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    parts: Vec<SnapshotPart>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotPart {
+    // "full" or the example's name
+    input: String,
+    part: u8,
+    answer: Answer,
+    #[serde(with = "crate::bench::nanos")]
+    elapsed: Duration,
+}
+
+impl SnapshotPart {
+    // Matches the labels the runner prints
+    fn label(&self) -> String {
+        match self.input.as_str() {
+            "full" => format!("Full - Part {}", self.part),
+            name => format!("Example {} - Part {}", name, self.part),
+        }
+    }
+}
+
+fn input_name(input: &InputKind) -> String {
+    match input {
+        InputKind::Example(name) => name.clone(),
+        InputKind::Full => "full".to_string(),
+    }
+}
+
+impl Snapshot {
+    pub fn new(results: &[PartResult]) -> Snapshot {
+        Snapshot {
+            // Parts that ran out of time show as not run next time
+            parts: results
+                .iter()
+                .filter_map(|x| {
+                    Some(SnapshotPart {
+                        input: input_name(&x.input),
+                        part: x.part.number(),
+                        answer: x.answer.clone().ok()?,
+                        elapsed: x.elapsed,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    // None when this day hasn't been watched before
+    pub fn load(path: &str) -> Result<Option<Snapshot>> {
+        store::load_json(path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        store::save_json(path, self)
+    }
+
+    fn get(&self, input: &str, part: u8) -> Option<&SnapshotPart> {
+        self.parts.iter().find(|x| x.input == input && x.part == part)
+    }
+}
+
+pub fn print_diff(previous: &Snapshot, current: &Snapshot) {
+    println!("<--------    Since Last Run    -------->");
+    for now in current.parts.iter() {
+        let label = now.label();
+        let Some(before) = previous.get(&now.input, now.part) else {
+            println!("{}: {} (new)", label, now.answer);
+            continue;
+        };
+
+        let timing = format!(
+            "{:.2?} -> {:.2?}, {}",
+            before.elapsed,
+            now.elapsed,
+            bench::speed_change(before.elapsed, now.elapsed)
+        );
+        match (now.answer == before.answer, now.answer.is_multiline()) {
+            (true, _) => println!("{}: {} ({})", label, now.answer, timing),
+            (false, false) => println!("{}: {} -> {} ({})", label, before.answer, now.answer, timing),
+            (false, true) => println!("{}: changed ({})\n{}", label, timing, now.answer),
+        }
+    }
+
+    for before in previous.parts.iter().filter(|x| current.get(&x.input, x.part).is_none()) {
+        println!("{}: {} (not run)", before.label(), before.answer);
+    }
+    println!();
+}
+
+// Modification times of the input files, a change in any of them means it's time to run again
+pub fn input_times(inputs: &Inputs) -> Vec<(String, Option<SystemTime>)> {
+    let sources = inputs.examples.iter().map(|x| &x.source).chain([&inputs.full]);
+    sources
+        .filter_map(|source| match source {
+            InputSource::File(path) => {
+                let modified = std::fs::metadata(path).and_then(|x| x.modified()).ok();
+                Some((path.clone(), modified))
+            }
+            InputSource::Stdin | InputSource::Text(_) => None,
+        })
+        .collect()
+}
@@ -0,0 +1,71 @@
+// Example and full input answers for every registered day, the full ones come from answers.json
+
+use aoc2022::day::{Part, RunOptions};
+use aoc2022::manifest::{Manifest, MANIFEST_FILE};
+use aoc2022::output::QuietReporter;
+use aoc2022::registry::{DayEntry, DAYS};
+
+// Runs one part of every implementation on every example, or on the full input, and checks it against the registry or the answers manifest
+fn check(entry: &DayEntry, examples: bool, part: Part) {
+    let manifest = Manifest::load(MANIFEST_FILE).unwrap();
+    let options = RunOptions {
+        part: Some(part),
+        run_example: examples,
+        run_full: !examples,
+        stop_on_mismatch: false,
+        timeout: None,
+    };
+
+    // Every implementation has to get the same answers as the first, expected or not
+    let mut first: Option<(&str, Vec<_>)> = None;
+    for implementation in entry.implementations {
+        let results = implementation.solver.run(&entry.inputs(&manifest), &options, &mut QuietReporter)
+            .unwrap_or_else(|e| panic!("Day {} {}: {}", entry.day, implementation.name, e));
+        assert!(!results.is_empty());
+        let answers: Vec<_> = results
+            .into_iter()
+            .map(|x| (x.answer.unwrap_or_else(|e| panic!("Day {} {}: {}", entry.day, implementation.name, e)), x.input, x.expected))
+            .collect();
+        for (answer, input, expected) in answers.iter() {
+            match expected {
+                Some(expected) => assert_eq!(answer, expected, "Day {} {} {:?}", entry.day, implementation.name, input),
+                None => println!("Day {} Part {} has no expected answer for {:?}", entry.day, part.number(), input),
+            }
+        }
+
+        let answers: Vec<_> = answers.into_iter().map(|(answer, input, _)| (input, answer)).collect();
+        match &first {
+            Some((name, first_answers)) => assert_eq!(&answers, first_answers, "Day {} {} against {}", entry.day, implementation.name, name),
+            None => first = Some((implementation.name, answers)),
+        }
+    }
+}
+
+// One test per input kind and part, each going through every registered day
+#[test]
+fn example_part_1() {
+    for entry in DAYS {
+        check(entry, true, Part::One);
+    }
+}
+
+#[test]
+fn example_part_2() {
+    for entry in DAYS {
+        check(entry, true, Part::Two);
+    }
+}
+
+#[test]
+fn full_part_1() {
+    for entry in DAYS {
+        check(entry, false, Part::One);
+    }
+}
+
+#[test]
+fn full_part_2() {
+    for entry in DAYS {
+        check(entry, false, Part::Two);
+    }
+}